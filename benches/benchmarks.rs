@@ -1,7 +1,11 @@
+use std::collections::HashSet;
+
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 use itertools::Itertools;
 
+use common::{BitGrid, SignedCoord};
+
 // Benchmarking two ways of checking whether a sequence of elements are unique
 pub fn benchmark_all_unique(c: &mut Criterion) {
     let sizes = (10..=50).step_by(10).chain((100..=1000).step_by(100));
@@ -12,16 +16,108 @@ pub fn benchmark_all_unique(c: &mut Criterion) {
             b.iter(|| black_box(&input).iter().all_unique())
         });
 
-        c.bench_function(format!("all_unique: tuple_combinations n={}", n).as_str(), |b| {
-            b.iter(|| {
-                black_box(&input)
-                    .iter()
-                    .tuple_combinations()
-                    .all(|(a, b)| a != b)
-            })
-        });
+        c.bench_function(
+            format!("all_unique: tuple_combinations n={}", n).as_str(),
+            |b| {
+                b.iter(|| {
+                    black_box(&input)
+                        .iter()
+                        .tuple_combinations()
+                        .all(|(a, b)| a != b)
+                })
+            },
+        );
+    }
+}
+
+// The real day9 and day14 solutions, which are generic over how they store visited coordinates.  Their mains
+// and tests aren't used here.
+#[allow(dead_code, unused_imports)]
+#[path = "../src/bin/day14.rs"]
+mod day14;
+#[allow(dead_code, unused_imports)]
+#[path = "../src/bin/day9.rs"]
+mod day9;
+
+// A deterministic xorshift generator, so each run benchmarks the same inputs
+struct XorShift(u64);
+
+impl XorShift {
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % n
+    }
+}
+
+// Rope motions shaped like a day9 input: moves of 1 to 20 in random directions
+fn day9_input(num_moves: usize) -> String {
+    let mut rng = XorShift(0x2545f4914f6cdd1d);
+    (0..num_moves)
+        .map(|_| {
+            let direction = ["U", "D", "L", "R"][rng.below(4) as usize];
+            format!("{direction} {}", rng.below(20) + 1)
+        })
+        .join("\n")
+}
+
+// Rock paths shaped like a day14 input: short zigzags of horizontal and vertical lines below the sand source
+fn day14_input(num_paths: usize) -> String {
+    let mut rng = XorShift(0x9e3779b97f4a7c15);
+    (0..num_paths)
+        .map(|_| {
+            let (mut x, mut y) = (460 + rng.below(80), 15 + rng.below(150));
+            let mut points = vec![format!("{x},{y}")];
+            for segment in 0..rng.below(4) + 1 {
+                let len = rng.below(8) + 1;
+                if segment % 2 == 0 {
+                    x += len;
+                } else {
+                    y += len;
+                }
+                points.push(format!("{x},{y}"));
+            }
+            points.join(" -> ")
+        })
+        .join("\n")
+}
+
+// Benchmarking the day9 and day14 simulations with their visited sets stored in a HashSet and in a BitGrid
+pub fn benchmark_visited_set(c: &mut Criterion) {
+    for num_moves in [200, 2000] {
+        let instructions = day9::parse_input(&day9_input(num_moves));
+        for num_knots in [2, 10] {
+            let name = format!("day9 moves={num_moves} knots={num_knots}");
+
+            c.bench_function(format!("{name}: HashSet").as_str(), |b| {
+                b.iter(|| {
+                    day9::count_visited::<HashSet<SignedCoord>>(black_box(&instructions), num_knots)
+                })
+            });
+
+            c.bench_function(format!("{name}: BitGrid").as_str(), |b| {
+                b.iter(|| day9::count_visited::<BitGrid>(black_box(&instructions), num_knots))
+            });
+        }
     }
+
+    let paths = day14::parse_input(&day14_input(150));
+
+    c.bench_function("day14 part 1: HashSet", |b| {
+        b.iter(|| day14::solve_part1::<HashSet<SignedCoord>>(black_box(&paths)))
+    });
+    c.bench_function("day14 part 1: BitGrid", |b| {
+        b.iter(|| day14::solve_part1::<BitGrid>(black_box(&paths)))
+    });
+
+    c.bench_function("day14 part 2: HashSet", |b| {
+        b.iter(|| day14::solve_part2::<HashSet<SignedCoord>>(black_box(&paths)))
+    });
+    c.bench_function("day14 part 2: BitGrid", |b| {
+        b.iter(|| day14::solve_part2::<BitGrid>(black_box(&paths)))
+    });
 }
 
-criterion_group!(benches, benchmark_all_unique);
+criterion_group!(benches, benchmark_all_unique, benchmark_visited_set);
 criterion_main!(benches);
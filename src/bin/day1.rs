@@ -1,27 +1,157 @@
 // https://adventofcode.com/2022/day/1
 
-use std::{fs::read_to_string, cmp::Reverse};
-
-use itertools::Itertools;
-
-fn main() {
-    let input = read_to_string("input/day1-input.txt").unwrap();
-
-    // Sum calories per elf, and sort in descending order
-    let calories_per_elf = input
-        .split("\n\n")
-        .map(|elf_input| {
-            elf_input
-                .lines()
-                .map(|l| l.parse::<u32>().unwrap())
-                .sum::<u32>()
-        })
-        .sorted_by_key(|&s| Reverse(s))
-        .collect_vec();
-
-    let part1_solution = calories_per_elf[0];
-    println!("Part 1 solution = {part1_solution}");
-
-    let part2_solution = calories_per_elf[0..3].iter().sum::<u32>();
-    println!("Part 2 solution = {part2_solution}");
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    env,
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+/// Sum calories per elf while streaming through the input, keeping only the k largest totals in a min-heap,
+/// so memory use doesn't depend on the input size.  Returns the top k in descending order, or an error if
+/// there are fewer than k elves.
+fn top_elves<R: BufRead>(mut reader: R, k: usize) -> Result<Vec<u64>> {
+    let mut top = BinaryHeap::with_capacity(k + 1);
+    let mut num_elves = 0;
+    let mut add_elf = |calories: u64| {
+        num_elves += 1;
+        top.push(Reverse(calories));
+        if top.len() > k {
+            top.pop();
+        }
+    };
+
+    let mut line = String::new();
+    let mut line_num = 0;
+    let mut current: Option<u64> = None;
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        line_num += 1;
+
+        let line = line.trim();
+        if line.is_empty() {
+            if let Some(calories) = current.take() {
+                add_elf(calories);
+            }
+            continue;
+        }
+
+        let item = line
+            .parse::<u64>()
+            .with_context(|| format!("Invalid calories on line {line_num}: {line:?}"))?;
+        current = Some(
+            current
+                .unwrap_or(0)
+                .checked_add(item)
+                .ok_or_else(|| anyhow!("Calorie total overflowed on line {line_num}"))?,
+        );
+    }
+    if let Some(calories) = current {
+        add_elf(calories);
+    }
+
+    if num_elves < k {
+        bail!("Need at least {k} elves but the input only has {num_elves}");
+    }
+
+    Ok(top
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(c)| c)
+        .collect())
+}
+
+/// Usage: day1 [--top K] [INPUT]
+fn parse_args() -> Result<(usize, String)> {
+    const USAGE: &str = "Usage: day1 [--top K] [INPUT]";
+    let mut k = 3;
+    let mut path = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--top" {
+            let value = args.next().ok_or_else(|| anyhow!("--top needs a value"))?;
+            k = value
+                .parse()
+                .ok()
+                .filter(|&k| k > 0)
+                .ok_or_else(|| anyhow!("--top must be a positive integer, got {value:?}"))?;
+        } else if arg.starts_with('-') {
+            bail!("Unknown option {arg:?}\n{USAGE}");
+        } else if path.is_some() {
+            bail!("Only one input file can be given\n{USAGE}");
+        } else {
+            path = Some(arg);
+        }
+    }
+
+    Ok((
+        k,
+        path.unwrap_or_else(|| "input/day1-input.txt".to_string()),
+    ))
+}
+
+fn main() -> Result<()> {
+    let (k, path) = parse_args()?;
+    let file = File::open(&path).with_context(|| format!("Can't open {path}"))?;
+
+    let top = top_elves(BufReader::new(file), k)?;
+
+    println!("Part 1 solution = {}", top[0]);
+    println!("Part 2 solution = {}", top.iter().sum::<u64>());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000
+";
+
+    #[test]
+    fn test_top_elves() {
+        assert_eq!(
+            top_elves(EXAMPLE.as_bytes(), 3).unwrap(),
+            vec![24000, 11000, 10000]
+        );
+        assert_eq!(top_elves(EXAMPLE.as_bytes(), 1).unwrap(), vec![24000]);
+
+        // runs of blank lines and a missing trailing newline don't create extra elves
+        let input = "\n\n1\n2\n\n\n\n5";
+        assert_eq!(top_elves(input.as_bytes(), 2).unwrap(), vec![5, 3]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = top_elves(EXAMPLE.as_bytes(), 6).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Need at least 6 elves but the input only has 5"
+        );
+
+        let err = top_elves("1\n\nx2\n".as_bytes(), 1).unwrap_err();
+        assert!(err.to_string().contains("line 3"));
+    }
 }
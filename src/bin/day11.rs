@@ -1,10 +1,15 @@
 // https://adventofcode.com/2022/day/11
 
 use std::fs::read_to_string;
+use std::ops::Add;
 
+use anyhow::{anyhow, Result};
 use itertools::Itertools;
 use regex::Regex;
 
+use common::cycle::sum_at;
+use common::math::checked_lcm_all;
+
 struct Monkey {
     items: Vec<usize>,
     operator: char,
@@ -65,10 +70,11 @@ Monkey \d+:
         .collect()
 }
 
-fn solve(monkeys: &Vec<Monkey>, num_rounds: usize, relief_factor: usize) -> usize {
-    // I can mod the levels by the product of the test divisors to keep them from growing too large, since I only 
-    // care about the remainders to implement the algorithm
-    let mod_factor: usize = monkeys.iter().map(|m| m.test_divisor).product();
+fn solve(monkeys: &[Monkey], num_rounds: usize, relief_factor: usize) -> Result<usize> {
+    // I can mod the levels by the lcm of the test divisors to keep them from growing too large, since I only
+    // care about the remainders mod each divisor to implement the algorithm
+    let mod_factor = checked_lcm_all(monkeys.iter().map(|m| m.test_divisor))
+        .ok_or_else(|| anyhow!("lcm of the test divisors overflows usize"))?;
 
     let mut all_items = monkeys.iter().map(|m| m.items.clone()).collect_vec();
     let mut counts = vec![0usize; monkeys.len()];
@@ -82,10 +88,16 @@ fn solve(monkeys: &Vec<Monkey>, num_rounds: usize, relief_factor: usize) -> usiz
             for worry_level in items {
                 let operand = monkey.operand.unwrap_or(worry_level);
                 let new_level = match monkey.operator {
-                    '+' => worry_level + operand,
-                    '*' => worry_level * operand,
+                    '+' => worry_level.checked_add(operand),
+                    '*' => worry_level.checked_mul(operand),
                     _ => unreachable!(),
-                } / relief_factor;
+                }
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Worry level overflowed: {worry_level} {} {operand}",
+                        monkey.operator
+                    )
+                })? / relief_factor;
 
                 let new_level = new_level % mod_factor;
 
@@ -99,13 +111,167 @@ fn solve(monkeys: &Vec<Monkey>, num_rounds: usize, relief_factor: usize) -> usiz
     }
 
     counts.sort();
-    counts[counts.len() - 2..counts.len()].iter().product()
+    Ok(counts[counts.len() - 2..counts.len()].iter().product())
+}
+
+/// Per-monkey inspection counts, added elementwise
+#[derive(Clone, Default)]
+struct Counts(Vec<usize>);
+
+impl Add for Counts {
+    type Output = Counts;
+
+    fn add(self, other: Counts) -> Counts {
+        let (mut longer, shorter) = if self.0.len() >= other.0.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        longer
+            .0
+            .iter_mut()
+            .zip(shorter.0)
+            .for_each(|(a, b)| *a += b);
+        longer
+    }
 }
 
-fn main() {
-    let input = read_to_string("input/day11-input.txt").unwrap();
+/// Follow one item through a round, starting with the monkey holding it.  Returns the monkey holding it and
+/// its worry level at the start of the next round, and which monkeys inspected it along the way.
+fn item_round(
+    monkeys: &[Monkey],
+    mod_factor: usize,
+    (mut monkey_id, mut worry_level): (usize, usize),
+) -> ((usize, usize), Vec<usize>) {
+    let mut inspected_by = vec![];
+
+    loop {
+        let monkey = &monkeys[monkey_id];
+        inspected_by.push(monkey_id);
+
+        let operand = monkey.operand.unwrap_or(worry_level);
+        worry_level = match monkey.operator {
+            '+' => worry_level + operand,
+            '*' => worry_level * operand,
+            _ => unreachable!(),
+        } % mod_factor;
+
+        let target = if worry_level % monkey.test_divisor == 0 {
+            monkey.if_true
+        } else {
+            monkey.if_false
+        };
+
+        // monkeys take turns in order, so a later monkey gets to throw the item again in the same round
+        if target <= monkey_id {
+            return ((target, worry_level), inspected_by);
+        }
+        monkey_id = target;
+    }
+}
+
+/// Same as solve without relief, but items never interact, so each one is a simulation on its own whose state
+/// (monkey, worry level mod the lcm) is finite.  Detecting each item's cycle makes the cost independent of the
+/// number of rounds.
+fn solve_with_cycles(monkeys: &[Monkey], num_rounds: usize) -> Result<usize> {
+    let mod_factor = checked_lcm_all(monkeys.iter().map(|m| m.test_divisor))
+        .ok_or_else(|| anyhow!("lcm of the test divisors overflows usize"))?;
+
+    // worry levels are kept below mod_factor, so this bounds both operations
+    let max_operand = monkeys.iter().filter_map(|m| m.operand).max().unwrap_or(0);
+    mod_factor
+        .checked_mul(mod_factor.max(max_operand))
+        .ok_or_else(|| anyhow!("Worry levels can overflow with lcm {mod_factor}"))?;
+
+    let step = |&state: &(usize, usize)| item_round(monkeys, mod_factor, state).0;
+    let inspections = |&state: &(usize, usize)| {
+        let mut counts = vec![0; monkeys.len()];
+        for monkey_id in item_round(monkeys, mod_factor, state).1 {
+            counts[monkey_id] += 1;
+        }
+        Counts(counts)
+    };
+
+    let Counts(mut counts) = monkeys
+        .iter()
+        .enumerate()
+        .flat_map(|(i, monkey)| monkey.items.iter().map(move |&item| (i, item % mod_factor)))
+        .map(|item| sum_at(&item, step, |&state| state, inspections, num_rounds))
+        .fold(Counts(vec![0; monkeys.len()]), Counts::add);
+
+    counts.sort();
+    counts[counts.len() - 2..counts.len()]
+        .iter()
+        .try_fold(1usize, |product, &count| product.checked_mul(count))
+        .ok_or_else(|| anyhow!("Monkey business overflows usize"))
+}
+
+fn main() -> Result<()> {
+    let input = read_to_string("input/day11-input.txt")?;
     let monkeys = parse_input(&input);
 
-    println!("Part 1 solution = {}", solve(&monkeys, 20, 3));
-    println!("Part 2 solution = {}", solve(&monkeys, 10000, 1));
+    println!("Part 1 solution = {}", solve(&monkeys, 20, 3)?);
+    println!("Part 2 solution = {}", solve(&monkeys, 10000, 1)?);
+
+    assert_eq!(
+        solve(&monkeys, 10000, 1)?,
+        solve_with_cycles(&monkeys, 10000)?
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1
+";
+
+    #[test]
+    fn test_solve() {
+        let monkeys = parse_input(&EXAMPLE.to_string());
+        assert_eq!(solve(&monkeys, 20, 3).unwrap(), 10605);
+        assert_eq!(solve(&monkeys, 10000, 1).unwrap(), 2713310158);
+    }
+
+    #[test]
+    fn test_solve_with_cycles() {
+        let monkeys = parse_input(&EXAMPLE.to_string());
+        for num_rounds in [1, 20, 1000, 10000] {
+            assert_eq!(
+                solve_with_cycles(&monkeys, num_rounds).unwrap(),
+                solve(&monkeys, num_rounds, 1).unwrap()
+            );
+        }
+
+        // far past anything solve could simulate in a test
+        assert!(solve_with_cycles(&monkeys, 100_000_000).is_ok());
+    }
 }
@@ -1,18 +1,13 @@
 // https://adventofcode.com/2022/day/12
 
-use std::{collections::HashMap, fs::read_to_string};
+use std::fs::read_to_string;
 
 use itertools::Itertools;
-use petgraph::{
-    algo::dijkstra::dijkstra,
-    graph::DiGraph,
-    visit::{GraphBase, Reversed},
-};
+use petgraph::{algo::dijkstra::dijkstra, visit::Reversed};
 
-use common::Grid;
+use common::{Coord, Grid, GridGraph};
 
-type HeightMap = DiGraph<usize, ()>;
-type NodeId = <HeightMap as GraphBase>::NodeId;
+type HeightMap = Grid<usize>;
 
 fn cell_height(ch: char) -> usize {
     let ch = match ch {
@@ -23,7 +18,12 @@ fn cell_height(ch: char) -> usize {
     (ch as usize) - ('a' as usize)
 }
 
-fn parse_input(input: &String) -> (HeightMap, NodeId, NodeId) {
+/// There's an edge from a cell to a neighbor that is at most 1 higher
+fn is_climbable(from_height: &usize, to_height: &usize) -> bool {
+    from_height + 1 >= *to_height
+}
+
+fn parse_input(input: &String) -> (HeightMap, Coord, Coord) {
     let grid = Grid::new(
         input
             .trim()
@@ -32,67 +32,43 @@ fn parse_input(input: &String) -> (HeightMap, NodeId, NodeId) {
             .collect_vec(),
     );
 
-    let mut graph = HeightMap::new();
-
-    let mut start_node = NodeId::default();
-    let mut end_node = NodeId::default();
-
-    let mut coord_node_map = HashMap::new();
-
-    // need to add nodes before we can add edges
-    for coord in grid.all_coords() {
-        let node_id = graph.add_node(cell_height(grid[coord]));
+    let start = grid.all_coords().find(|&c| grid[c] == 'S').unwrap();
+    let end = grid.all_coords().find(|&c| grid[c] == 'E').unwrap();
 
-        coord_node_map.insert(coord, node_id);
-
-        if grid[coord] == 'S' {
-            start_node = node_id;
-        } else if grid[coord] == 'E' {
-            end_node = node_id;
-        }
-    }
-
-    // add edges
-    for from_coord in grid.all_coords() {
-        let from_val = grid[from_coord];
-        let from_node = coord_node_map[&from_coord];
-
-        for nc in grid.neighbor_coords(from_coord) {
-            if cell_height(from_val) + 1 >= cell_height(grid[nc]) {
-                graph.add_edge(from_node, coord_node_map[&nc], ());
-            }
-        }
-    }
+    let map = Grid::new(
+        (0..grid.num_rows())
+            .map(|row| grid[row].iter().map(|&ch| cell_height(ch)).collect())
+            .collect(),
+    );
 
-    (graph, start_node, end_node)
+    (map, start, end)
 }
 
-fn solve_part1(graph: &HeightMap, start_node: NodeId, end_node: NodeId) -> usize {
-    let res = dijkstra(graph, start_node, Some(end_node), |_| 1);
-    res[&end_node]
+fn solve_part1(map: &HeightMap, start: Coord, end: Coord) -> usize {
+    let graph = GridGraph::new(map, is_climbable);
+    let res = dijkstra(&graph, start, Some(end), |_| 1);
+    res[&end]
 }
 
-fn solve_part2(graph: &HeightMap, end_node: NodeId) -> usize {
-    // find lengths of shortest paths from all nodes to end_node.  I do this by reversing the edges in the graph, and then asking
-    // for the shortest paths from the end_node to all other nodes.
-    let all_lengths = dijkstra(Reversed(graph), end_node, None, |_| 1usize);
+fn solve_part2(map: &HeightMap, end: Coord) -> usize {
+    // find lengths of shortest paths from all nodes to end.  I do this by reversing the edges in the graph, and then asking
+    // for the shortest paths from end to all other nodes.
+    let graph = GridGraph::new(map, is_climbable);
+    let all_lengths = dijkstra(Reversed(&graph), end, None, |_| 1usize);
 
     // find the min length after keeping only lengths starting from nodes with height 0
     all_lengths
         .into_iter()
-        .filter_map(|(node_id, len)| if graph[node_id] == 0 { Some(len) } else { None })
+        .filter_map(|(coord, len)| if map[coord] == 0 { Some(len) } else { None })
         .min()
         .unwrap()
 }
 
 fn main() {
     let input = read_to_string("input/day12-input.txt").unwrap();
-    let (graph, start_node, end_node) = parse_input(&input);
+    let (map, start, end) = parse_input(&input);
 
-    println!(
-        "Part 1 solution = {}",
-        solve_part1(&graph, start_node, end_node)
-    );
+    println!("Part 1 solution = {}", solve_part1(&map, start, end));
 
-    println!("Part 2 solution = {}", solve_part2(&graph, end_node));
+    println!("Part 2 solution = {}", solve_part2(&map, end));
 }
@@ -1,33 +1,35 @@
 // https://adventofcode.com/2022/day/14
 
-use std::{collections::HashSet, fs::read_to_string};
+use std::fs::read_to_string;
 
 use itertools::Itertools;
 
-type Coord = (isize, isize);
+use common::{BitGrid, CoordSet, Direction, Point2};
+
+type Coord = Point2<isize>;
 type Path = Vec<Coord>;
 
 #[derive(Default)]
-struct Scan {
-    grid: HashSet<Coord>,
+struct Scan<S> {
+    grid: S,
     max_row: isize,
 
     // If set, behaves as if there is an infinite horizontal line at this row
     floor: Option<isize>,
 }
 
-impl Scan {
-    fn new() -> Scan {
+impl<S: CoordSet> Scan<S> {
+    fn new() -> Scan<S> {
         Scan::default()
     }
 
     fn is_set(&self, coord: Coord) -> bool {
-        self.grid.contains(&coord) || self.floor.map_or(false, |f| coord.0 == f)
+        self.grid.contains(coord.to_row_col()) || self.floor == Some(coord.y)
     }
 
     fn set(&mut self, coord: Coord) {
-        if self.grid.insert(coord) {
-            self.max_row = coord.0.max(self.max_row);
+        if self.grid.insert(coord.to_row_col()) {
+            self.max_row = coord.y.max(self.max_row);
         }
     }
 
@@ -37,7 +39,7 @@ impl Scan {
     }
 }
 
-fn parse_input(input: &String) -> Vec<Path> {
+pub(crate) fn parse_input(input: &String) -> Vec<Path> {
     input
         .trim()
         .lines()
@@ -45,22 +47,22 @@ fn parse_input(input: &String) -> Vec<Path> {
             line.split(" -> ")
                 .map(|point_str| {
                     let (x, y) = point_str.split_once(",").unwrap();
-                    (y.parse().unwrap(), x.parse().unwrap())
+                    Point2::new(x.parse().unwrap(), y.parse().unwrap())
                 })
                 .collect()
         })
         .collect()
 }
 
-fn create_scan(rock_paths: &Vec<Path>, with_floor: bool) -> Scan {
+fn create_scan<S: CoordSet>(rock_paths: &Vec<Path>, with_floor: bool) -> Scan<S> {
     let mut scan = Scan::new();
 
     for path in rock_paths.iter() {
-        for (&(row0, col0), &(row1, col1)) in path.iter().tuple_windows() {
-            let (from_row, to_row) = (row0.min(row1), row0.max(row1));
-            let (from_col, to_col) = (col0.min(col1), col0.max(col1));
+        for (&from, &to) in path.iter().tuple_windows() {
+            let (from_row, to_row) = (from.y.min(to.y), from.y.max(to.y));
+            let (from_col, to_col) = (from.x.min(to.x), from.x.max(to.x));
             for coord in (from_row..=to_row).cartesian_product(from_col..=to_col) {
-                scan.set(coord);
+                scan.set(Point2::from_row_col(coord));
             }
         }
     }
@@ -74,36 +76,41 @@ fn create_scan(rock_paths: &Vec<Path>, with_floor: bool) -> Scan {
 
 /// Simulate dropping a unit of sand.  Returns Some(Some(coord)) if unit came to rest, Some(None) if unit never hit an obstacle, or
 /// None if starting location is full
-fn simulate_drop(scan: &Scan) -> Option<Option<Coord>> {
-    let mut cur_row: isize = 0;
-    let mut cur_col: isize = 500;
+fn simulate_drop<S: CoordSet>(scan: &Scan<S>) -> Option<Option<Coord>> {
+    let mut cur = Point2::new(500, 0);
 
-    if scan.is_set((cur_row, cur_col)) {
+    if scan.is_set(cur) {
         return None;
     }
 
+    let down = Direction::Down.delta();
+    let moves = [
+        down,
+        down + Direction::Left.delta(),
+        down + Direction::Right.delta(),
+    ];
+
     loop {
         let mut moved = false;
-        for next_col in [cur_col, cur_col - 1, cur_col + 1] {
-            let next_row = cur_row + 1;
-            if next_row > scan.max_row {
+        for next in moves.map(|m| cur + m) {
+            if next.y > scan.max_row {
                 return Some(None);
-            } else if !scan.is_set((next_row, next_col)) {
-                cur_row = next_row;
-                cur_col = next_col;
+            } else if !scan.is_set(next) {
+                cur = next;
                 moved = true;
                 break;
             }
         }
 
         if !moved {
-            return Some(Some((cur_row, cur_col)));
+            return Some(Some(cur));
         }
     }
 }
 
-fn solve_part1(paths: &Vec<Path>) -> usize {
-    let mut scan = create_scan(paths, false);
+/// Generic over the set the scan is stored in, so the benchmarks can compare a BitGrid with a HashSet
+pub(crate) fn solve_part1<S: CoordSet>(paths: &Vec<Path>) -> usize {
+    let mut scan = create_scan::<S>(paths, false);
 
     let mut count: usize = 0;
 
@@ -115,8 +122,8 @@ fn solve_part1(paths: &Vec<Path>) -> usize {
     count
 }
 
-fn solve_part2(paths: &Vec<Path>) -> usize {
-    let mut scan = create_scan(paths, true);
+pub(crate) fn solve_part2<S: CoordSet>(paths: &Vec<Path>) -> usize {
+    let mut scan = create_scan::<S>(paths, true);
 
     let mut count: usize = 0;
 
@@ -134,6 +141,6 @@ fn main() {
 
     let rock_paths = parse_input(&input);
 
-    println!("Part 1 solution = {}", solve_part1(&rock_paths));
-    println!("Part 2 solution = {}", solve_part2(&rock_paths));
+    println!("Part 1 solution = {}", solve_part1::<BitGrid>(&rock_paths));
+    println!("Part 2 solution = {}", solve_part2::<BitGrid>(&rock_paths));
 }
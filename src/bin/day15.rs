@@ -1,4 +1,4 @@
-// https://adventofcode.com/2022/day/14
+// https://adventofcode.com/2022/day/15
 
 use std::fs::read_to_string;
 
@@ -6,11 +6,12 @@ use anyhow::{anyhow, Result};
 use itertools::Itertools;
 use regex::Regex;
 
-#[derive(Debug)]
-struct Point {
-    x: isize,
-    y: isize,
-}
+use common::{
+    interval::{Interval, IntervalSet},
+    Point2,
+};
+
+type Point = Point2<isize>;
 
 #[derive(Debug)]
 struct Sensor {
@@ -31,15 +32,8 @@ fn parse_input(input: &String) -> Result<Vec<Sensor>> {
                 .captures(line)
                 .ok_or(anyhow!("Match failed for line {line:?}"))?;
 
-            let location = Point {
-                x: caps["sx"].parse()?,
-                y: caps["sy"].parse()?,
-            };
-
-            let nearest_beacon = Point {
-                x: caps["bx"].parse()?,
-                y: caps["by"].parse()?,
-            };
+            let location = Point::new(caps["sx"].parse()?, caps["sy"].parse()?);
+            let nearest_beacon = Point::new(caps["bx"].parse()?, caps["by"].parse()?);
 
             Ok(Sensor {
                 location,
@@ -49,62 +43,81 @@ fn parse_input(input: &String) -> Result<Vec<Sensor>> {
         .collect()
 }
 
-fn manhattan_distance(p1: &Point, p2: &Point) -> usize {
-    p1.x.abs_diff(p2.x) + p1.y.abs_diff(p2.y)
+/// The x ranges on row y that are within some sensor's range, i.e. closer to the sensor than its nearest beacon or
+/// as close
+fn row_coverage(sensors: &[Sensor], y: isize) -> IntervalSet<isize> {
+    sensors
+        .iter()
+        .filter_map(|sensor| {
+            let dist = sensor.location.manhattan_distance(sensor.nearest_beacon);
+            let horizontal_dist = dist - (sensor.location.y.abs_diff(y) as isize);
+
+            if horizontal_dist < 0 {
+                None
+            } else {
+                Some(Interval::new(
+                    sensor.location.x - horizontal_dist,
+                    sensor.location.x + horizontal_dist,
+                ))
+            }
+        })
+        .collect()
 }
 
-fn solve_part1(sensors: &Vec<Sensor>, target_row: isize) -> usize {
-    // ranges that overlap target_row
-    let mut x_ranges: Vec<(isize, isize)> = vec![];
-
-    for sensor in sensors.iter() {
-        let dist = manhattan_distance(&sensor.location, &sensor.nearest_beacon) as isize;
-
-        if !((sensor.location.y - dist)..=(sensor.location.y + dist)).contains(&target_row)
-            || (sensor.nearest_beacon.y.abs_diff(target_row) as isize == dist)
-        {
-            continue;
-        }
-
-        // calculate the range of row target_row in sensor's area that can't contain a beacon
-        let horizontal_dist = dist - (sensor.location.y.abs_diff(target_row) as isize);
-
-        let mut left = sensor.location.x - horizontal_dist;
-        let mut right = sensor.location.x + horizontal_dist;
+fn solve_part1(sensors: &[Sensor], target_row: isize) -> usize {
+    // positions on target_row that can't contain a beacon are the covered positions, except for known beacons
+    let beacons: IntervalSet<isize> = sensors
+        .iter()
+        .filter(|sensor| sensor.nearest_beacon.y == target_row)
+        .map(|sensor| Interval::new(sensor.nearest_beacon.x, sensor.nearest_beacon.x))
+        .collect();
+
+    row_coverage(sensors, target_row)
+        .difference(&beacons)
+        .covered_len() as usize
+}
 
-        // account of the case where the beacon is on target row
-        if sensor.nearest_beacon.x == left {
-            left += 1;
-        } else if sensor.nearest_beacon.x == right {
-            right -= 1;
+/// The rows that could hold the only uncovered position in the search area.  Its covered neighbours put it just out
+/// of range of some sensors, on their boundary diagonals x + y = c or x - y = c.  If every such diagonal through it
+/// runs the same way, the diagonal neighbour along that line is covered by a sensor that only reaches it corner to
+/// corner, which puts the position one further out on a diagonal running the other way.  So it's where diagonals
+/// of each kind cross, unless it's on the first or last row.
+fn candidate_rows(sensors: &[Sensor], max_coord: isize) -> Vec<isize> {
+    let mut sums = vec![];
+    let mut differences = vec![];
+    for sensor in sensors {
+        let Point { x, y } = sensor.location;
+        let dist = sensor.location.manhattan_distance(sensor.nearest_beacon);
+        for offset in [dist + 1, dist + 2] {
+            sums.extend([x + y - offset, x + y + offset]);
+            differences.extend([x - y - offset, x - y + offset]);
         }
-
-        x_ranges.push((left, right));
     }
 
-    // now calculate how many unique elements of target_row are covered by x_ranges
-
-    // first merge overlapping ranges
-    let merged_ranges = x_ranges.into_iter().sorted_by_key(|r| r.0).fold(
-        vec![],
-        |mut merged, range @ (left, right)| {
-            if let Some((_, prev_right)) = merged.last_mut() {
-                if *prev_right >= left {
-                    *prev_right = right.max(*prev_right);
-                } else {
-                    merged.push(range);
-                }
-            } else {
-                merged.push(range);
-            }
-            merged
-        },
-    );
+    sums.iter()
+        .cartesian_product(&differences)
+        .filter(|(sum, difference)| (*sum - *difference) % 2 == 0)
+        .map(|(sum, difference)| (sum - difference) / 2)
+        .chain([0, max_coord])
+        .filter(|y| (0..=max_coord).contains(y))
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+fn solve_part2(sensors: &[Sensor], max_coord: isize) -> isize {
+    // the distress beacon is at the only position in the search area not covered by any sensor
+    let bounds = Interval::new(0, max_coord);
 
-    merged_ranges
+    candidate_rows(sensors, max_coord)
         .into_iter()
-        .map(|(left, right)| (right - left + 1) as usize)
-        .sum()
+        .find_map(|y| {
+            let gaps = row_coverage(sensors, y).complement(bounds);
+            gaps.intervals()
+                .first()
+                .map(|gap| gap.start() * 4000000 + y)
+        })
+        .expect("Failed to find distress beacon")
 }
 
 fn main() {
@@ -112,4 +125,53 @@ fn main() {
     let sensors = parse_input(&input).unwrap();
 
     println!("Part 1 solution = {}", solve_part1(&sensors, 2000000));
+    println!("Part 2 solution = {}", solve_part2(&sensors, 4000000));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "
+Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+
+    #[test]
+    fn test_part1() {
+        let sensors = parse_input(&EXAMPLE.to_string()).unwrap();
+        assert_eq!(solve_part1(&sensors, 10), 26);
+    }
+
+    #[test]
+    fn test_part2() {
+        let sensors = parse_input(&EXAMPLE.to_string()).unwrap();
+        assert_eq!(solve_part2(&sensors, 20), 56000011);
+    }
+
+    #[test]
+    fn test_part2_corner_to_corner() {
+        // the gap at (10, 10) is between two ranges with boundaries along x + y = 20, and the sensors at (20, 0)
+        // and (0, 20) only cover the diagonal neighbours along that line corner to corner
+        let input = "
+Sensor at x=5, y=5: closest beacon is at x=14, y=5
+Sensor at x=15, y=15: closest beacon is at x=6, y=15
+Sensor at x=20, y=0: closest beacon is at x=2, y=0
+Sensor at x=0, y=20: closest beacon is at x=0, y=2
+Sensor at x=0, y=0: closest beacon is at x=1, y=0
+Sensor at x=20, y=20: closest beacon is at x=19, y=20";
+        let sensors = parse_input(&input.to_string()).unwrap();
+        assert_eq!(solve_part2(&sensors, 20), 40000010);
+    }
 }
@@ -1,18 +1,17 @@
 // https://adventofcode.com/2022/day/2
 
-use std::fs::read_to_string;
+use std::{env, fs::read_to_string};
 
+use anyhow::{anyhow, bail, Context, Result};
 use itertools::Itertools;
 
-// NOTE: The order of Move and Outcome enum members is significant, because the numeric values are
-// used by the algorithm.
-
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
-enum Move {
-    Rock,
-    Paper,
-    Scissor,
-}
+/// The standard game, in the rules format described on `parse_rules`
+const DEFAULT_RULES: &str = "
+# name     opponent  me  score  outcome vs Rock, Paper, Scissors
+Rock       A         X   1      D L W
+Paper      B         Y   2      W D L
+Scissors   C         Z   3      L W D
+";
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 enum Outcome {
@@ -21,98 +20,664 @@ enum Outcome {
     Win,
 }
 
-impl TryFrom<i32> for Move {
-    type Error = String;
+impl Outcome {
+    fn score(self) -> usize {
+        match self {
+            Outcome::Lose => 0,
+            Outcome::Draw => 3,
+            Outcome::Win => 6,
+        }
+    }
 
-    fn try_from(value: i32) -> Result<Self, Self::Error> {
-        match value {
-            0 => Ok(Move::Rock),
-            1 => Ok(Move::Paper),
-            2 => Ok(Move::Scissor),
-            _ => Err(format!("Invalid move: {value}")),
+    /// The outcome for the other player
+    fn opposite(self) -> Outcome {
+        match self {
+            Outcome::Lose => Outcome::Win,
+            Outcome::Draw => Outcome::Draw,
+            Outcome::Win => Outcome::Lose,
         }
     }
 }
 
-impl TryFrom<char> for Move {
+impl TryFrom<char> for Outcome {
     type Error = String;
 
     fn try_from(value: char) -> Result<Self, Self::Error> {
-        ((value as i32) - ('A' as i32)).try_into()
+        match value {
+            'L' => Ok(Outcome::Lose),
+            'D' => Ok(Outcome::Draw),
+            'W' => Ok(Outcome::Win),
+            _ => Err(format!("Invalid outcome: {value:?}")),
+        }
     }
 }
 
-impl Move {
-    /// Return the move that would beat self
-    fn winning_move(&self) -> Move {
-        Move::try_from((*self as i32 + 1) % 3).unwrap()
+/// (opponent code, my code) from one line of the strategy guide
+type Round = (char, char);
+
+/// A move, as an index into the rows of the `Rules`
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+struct Move(usize);
+
+#[derive(Debug)]
+struct MoveRule {
+    name: String,
+    opponent_code: char,
+    my_code: char,
+    score: usize,
+}
+
+/// A cyclic game with an odd number of moves, where every move beats half of the others and loses to the
+/// other half
+#[derive(Debug)]
+struct Rules {
+    moves: Vec<MoveRule>,
+    // outcomes[mine][theirs]
+    outcomes: Vec<Vec<Outcome>>,
+}
+
+impl Rules {
+    fn opponent_move(&self, code: char) -> Result<Move> {
+        self.moves
+            .iter()
+            .position(|m| m.opponent_code == code)
+            .map(Move)
+            .ok_or_else(|| anyhow!("Unknown opponent move code {code:?}"))
     }
 
-    /// Return the move that would lose to self
-    fn losing_move(&self) -> Move {
-        Move::try_from((*self as i32 - 1).rem_euclid(3)).unwrap()
+    fn my_move(&self, code: char) -> Result<Move> {
+        self.moves
+            .iter()
+            .position(|m| m.my_code == code)
+            .map(Move)
+            .ok_or_else(|| anyhow!("Unknown move code {code:?}"))
+    }
+
+    /// The highest scoring move that gets the desired outcome against opponent_move.  The table is balanced,
+    /// so there's always at least one.
+    fn move_for_outcome(&self, opponent_move: Move, outcome: Outcome) -> Move {
+        (0..self.moves.len())
+            .map(Move)
+            .filter(|&m| get_round_outcome(self, opponent_move, m) == outcome)
+            .max_by_key(|m| self.moves[m.0].score)
+            .unwrap()
     }
 }
 
-fn get_round_outcome(opponent_move: Move, my_move: Move) -> Outcome {
-    if my_move == opponent_move {
-        Outcome::Draw
-    } else if my_move == opponent_move.winning_move() {
-        Outcome::Win
-    } else {
-        Outcome::Lose
+/// Parse a payoff table.  Each non-blank line that doesn't start with `#` describes one move:
+///
+/// ```text
+/// name  opponent_code  my_code  score  outcome...
+/// ```
+///
+/// where the outcomes are one W, D or L per move, in table order, giving the result of playing this move
+/// against that one.  The table must have an odd number of moves, each must draw only against itself, and
+/// each must beat exactly half of the others.
+fn parse_rules(text: &str) -> Result<Rules> {
+    let rows = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_num, line)| (line_num, line.split_whitespace().collect_vec()))
+        .collect_vec();
+
+    let n = rows.len();
+    if n % 2 == 0 {
+        bail!("Rules must have an odd number of moves, found {n}");
+    }
+
+    let single_char = |token: &str, line_num: usize| {
+        token.chars().exactly_one().map_err(|_| {
+            anyhow!("Expected a single character on rules line {line_num}, found {token:?}")
+        })
+    };
+
+    let mut moves = vec![];
+    let mut outcomes = vec![];
+    for (line_num, tokens) in &rows {
+        let line_num = *line_num;
+        if tokens.len() != 4 + n {
+            bail!(
+                "Rules line {line_num} should have a name, two codes, a score and {n} outcomes, found {:?}",
+                tokens.join(" ")
+            );
+        }
+
+        moves.push(MoveRule {
+            name: tokens[0].to_string(),
+            opponent_code: single_char(tokens[1], line_num)?,
+            my_code: single_char(tokens[2], line_num)?,
+            score: tokens[3]
+                .parse()
+                .with_context(|| format!("Invalid score on rules line {line_num}"))?,
+        });
+
+        outcomes.push(
+            tokens[4..]
+                .iter()
+                .map(|&token| {
+                    Outcome::try_from(single_char(token, line_num)?).map_err(|e| anyhow!(e))
+                })
+                .collect::<Result<Vec<_>>>()
+                .with_context(|| format!("Invalid outcome on rules line {line_num}"))?,
+        );
     }
+
+    for (codes, which) in [
+        (
+            moves.iter().map(|m| m.opponent_code).collect_vec(),
+            "opponent",
+        ),
+        (moves.iter().map(|m| m.my_code).collect_vec(), "player"),
+    ] {
+        if let Some(code) = codes.iter().duplicates().next() {
+            bail!("Duplicate {which} code {code:?} in rules");
+        }
+    }
+
+    for i in 0..n {
+        for j in 0..n {
+            let expected = if i == j {
+                Outcome::Draw
+            } else {
+                outcomes[j][i].opposite()
+            };
+            if outcomes[i][j] != expected || (i != j && outcomes[i][j] == Outcome::Draw) {
+                bail!(
+                    "Inconsistent rules: {} vs {} is {:?}, but {} vs {} is {:?}",
+                    moves[i].name,
+                    moves[j].name,
+                    outcomes[i][j],
+                    moves[j].name,
+                    moves[i].name,
+                    outcomes[j][i]
+                );
+            }
+        }
+
+        let wins = outcomes[i].iter().filter(|&&o| o == Outcome::Win).count();
+        if wins != n / 2 {
+            bail!(
+                "Unbalanced rules: {} beats {wins} moves, but every move should beat {}",
+                moves[i].name,
+                n / 2
+            );
+        }
+    }
+
+    Ok(Rules { moves, outcomes })
+}
+
+fn get_round_outcome(rules: &Rules, opponent_move: Move, my_move: Move) -> Outcome {
+    rules.outcomes[my_move.0][opponent_move.0]
 }
 
-fn get_round_score(opponent_move: Move, my_move: Move) -> usize {
-    let outcome = get_round_outcome(opponent_move, my_move);
+fn get_round_score(rules: &Rules, opponent_move: Move, my_move: Move) -> usize {
+    let outcome = get_round_outcome(rules, opponent_move, my_move);
 
-    (my_move as usize) + 1 + (outcome as usize) * 3
+    rules.moves[my_move.0].score + outcome.score()
 }
 
-fn solve_part1(rounds: &[(char, char)]) -> usize {
-    let scores = rounds.iter().map(|&(opponent_char, my_char)| {
-        let opponent_move = Move::try_from(opponent_char).unwrap();
+fn solve_part1(rules: &Rules, rounds: &[Round]) -> Result<usize> {
+    rounds
+        .iter()
+        .map(|&(opponent_char, my_char)| {
+            // for part 1, the second column is my move
+            let opponent_move = rules.opponent_move(opponent_char)?;
+            let my_move = rules.my_move(my_char)?;
+
+            Ok(get_round_score(rules, opponent_move, my_move))
+        })
+        .sum()
+}
 
-        // for part 1, assume X,Y,Z map to A,B,C
-        let my_char = char::from_u32((my_char as u32) - ('X' as u32) + ('A' as u32)).unwrap();
-        let my_move = Move::try_from(my_char).unwrap();
+fn solve_part2(rules: &Rules, rounds: &[Round]) -> Result<usize> {
+    rounds
+        .iter()
+        .map(|&(opponent_char, my_char)| {
+            let opponent_move = rules.opponent_move(opponent_char)?;
 
-        get_round_score(opponent_move, my_move)
-    });
+            // for part 2, X means we should lose, Y means we should draw, and Z means we should win
+            let outcome = match my_char {
+                'X' => Outcome::Lose,
+                'Y' => Outcome::Draw,
+                'Z' => Outcome::Win,
+                _ => bail!("Unexpected outcome char '{my_char}'"),
+            };
+            let my_move = rules.move_for_outcome(opponent_move, outcome);
 
-    scores.sum()
+            Ok(get_round_score(rules, opponent_move, my_move))
+        })
+        .sum()
 }
 
-fn solve_part2(rounds: &[(char, char)]) -> usize {
-    let scores = rounds.iter().map(|&(opponent_char, my_char)| {
-        let opponent_move = Move::try_from(opponent_char).unwrap();
+/// Split off an optional rules header, which starts with a `[rules]` line and ends at the first blank line,
+/// and parse the rounds that follow
+fn parse_input(input: &str) -> Result<(Option<&str>, Vec<Round>)> {
+    let (rules, rounds) = match input.strip_prefix("[rules]\n") {
+        Some(rest) => match rest.split_once("\n\n") {
+            Some((rules, rounds)) => (Some(rules), rounds),
+            None => bail!("The [rules] header must be followed by a blank line"),
+        },
+        None => (None, input),
+    };
 
-        // for part 2, assume X means we should lose, Y means we should draw, and Z means we should win
-        let my_move = match my_char {
-            'X' => opponent_move.losing_move(),
-            'Y' => opponent_move,
-            'Z' => opponent_move.winning_move(),
-            _ => panic!("Unexpected move char '{my_char}'"),
-        };
+    let rounds = rounds
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.chars().collect_vec()[..] {
+            [opponent, ' ', me] => Ok((opponent, me)),
+            _ => Err(anyhow!("Invalid round {line:?}")),
+        })
+        .collect::<Result<Vec<_>>>()?;
 
-        get_round_score(opponent_move, my_move)
-    });
+    Ok((rules, rounds))
+}
 
-    scores.sum()
+/// Optimal mixed strategies and value of a two-player zero-sum game
+#[derive(Debug)]
+struct GameSolution {
+    /// expected payoff to the row player when both play optimally
+    value: f64,
+    row_strategy: Vec<f64>,
+    col_strategy: Vec<f64>,
 }
 
-fn main() {
-    let input = read_to_string("input/day2-input.txt").unwrap();
-    let rounds: Vec<(char, char)> = input
-        .lines()
-        .map(|line| {
-            let chars = line.chars().collect_vec();
-            assert_eq!(chars.len(), 3);
-            (chars[0], chars[2])
+const EPSILON: f64 = 1e-9;
+
+/// Maximize c·x subject to a·x <= b and x >= 0, where b >= 0 so the all-slack basis is feasible.  Uses
+/// Bland's rule to choose pivots, which can't cycle.  Returns the optimal value, the primal solution and the
+/// dual solution, or None if the problem is unbounded.
+fn simplex(a: &[Vec<f64>], b: &[f64], c: &[f64]) -> Option<(f64, Vec<f64>, Vec<f64>)> {
+    let (m, n) = (b.len(), c.len());
+
+    // each tableau row is [a | identity for the slacks | b], and the objective row holds the reduced costs
+    let mut tableau = (0..m)
+        .map(|i| {
+            let mut row = a[i].clone();
+            row.extend((0..m).map(|j| if i == j { 1.0 } else { 0.0 }));
+            row.push(b[i]);
+            row
+        })
+        .collect_vec();
+    let mut objective = c.iter().map(|&c| -c).collect_vec();
+    objective.resize(n + m + 1, 0.0);
+    let mut basis = (n..n + m).collect_vec();
+
+    // entering variable: the lowest numbered one whose reduced cost can improve the objective
+    while let Some(entering) = (0..n + m).find(|&j| objective[j] < -EPSILON) {
+        // leaving variable: the tightest ratio test, breaking ties by lowest numbered basic variable
+        let leaving = (0..m)
+            .filter(|&i| tableau[i][entering] > EPSILON)
+            .min_by(|&i, &j| {
+                let ratio_i = tableau[i][n + m] / tableau[i][entering];
+                let ratio_j = tableau[j][n + m] / tableau[j][entering];
+                if (ratio_i - ratio_j).abs() < EPSILON {
+                    basis[i].cmp(&basis[j])
+                } else {
+                    ratio_i.total_cmp(&ratio_j)
+                }
+            })?;
+
+        let pivot = tableau[leaving][entering];
+        tableau[leaving].iter_mut().for_each(|v| *v /= pivot);
+        let pivot_row = tableau[leaving].clone();
+
+        for (i, row) in tableau.iter_mut().enumerate() {
+            let factor = row[entering];
+            if i != leaving && factor != 0.0 {
+                row.iter_mut()
+                    .zip(&pivot_row)
+                    .for_each(|(v, p)| *v -= factor * p);
+            }
+        }
+        let factor = objective[entering];
+        objective
+            .iter_mut()
+            .zip(&pivot_row)
+            .for_each(|(v, p)| *v -= factor * p);
+
+        basis[leaving] = entering;
+    }
+
+    let mut primal = vec![0.0; n];
+    for (i, &var) in basis.iter().enumerate() {
+        if var < n {
+            primal[var] = tableau[i][n + m];
+        }
+    }
+    let dual = objective[n..n + m].to_vec();
+
+    Some((objective[n + m], primal, dual))
+}
+
+/// Solve the zero-sum game where payoffs[i][j] is what the row player wins when playing i against j.
+///
+/// Shifting the payoffs to be positive doesn't change the optimal strategies, and makes the value positive.
+/// Then the column player's problem is the LP: maximize Σw subject to payoffs·w <= 1 and w >= 0, whose
+/// optimum is 1/value, and the row player's strategy comes from its dual.
+fn solve_zero_sum_game(payoffs: &[Vec<f64>]) -> GameSolution {
+    let min_payoff = payoffs
+        .iter()
+        .flatten()
+        .copied()
+        .fold(f64::INFINITY, f64::min);
+    let shift = 1.0 - min_payoff;
+    let shifted = payoffs
+        .iter()
+        .map(|row| row.iter().map(|p| p + shift).collect_vec())
+        .collect_vec();
+
+    let (num_rows, num_cols) = (payoffs.len(), payoffs[0].len());
+    // every entry is at least 1, so w is bounded by 1 and the problem is never unbounded
+    let (total, w, u) = simplex(&shifted, &vec![1.0; num_rows], &vec![1.0; num_cols]).unwrap();
+
+    let shifted_value = 1.0 / total;
+    GameSolution {
+        value: shifted_value - shift,
+        row_strategy: u.iter().map(|u| u * shifted_value).collect(),
+        col_strategy: w.iter().map(|w| w * shifted_value).collect(),
+    }
+}
+
+/// Answers to analytical questions about the strategy guide, using the part 1 interpretation
+#[derive(Debug)]
+struct Analysis {
+    guide_score: usize,
+    /// the score if every round were played with the best move against the opponent's move
+    max_score: usize,
+    /// 1-based numbers of the rounds the guide loses
+    lost_rounds: Vec<usize>,
+    /// how often the opponent plays each move
+    opponent_frequencies: Vec<f64>,
+    /// the expected score of each move against the opponent's frequencies
+    expected_scores: Vec<f64>,
+    best_response: Move,
+    /// the minimax strategy for the game where payoff is my score minus the opponent's score
+    equilibrium: GameSolution,
+}
+
+fn analyze(rules: &Rules, rounds: &[Round]) -> Result<Analysis> {
+    let n = rules.moves.len();
+    let moves = (0..n).map(Move).collect_vec();
+
+    let mut max_score = 0;
+    let mut lost_rounds = vec![];
+    let mut opponent_counts = vec![0; n];
+    for (i, &(opponent_char, my_char)) in rounds.iter().enumerate() {
+        let opponent_move = rules.opponent_move(opponent_char)?;
+        let my_move = rules.my_move(my_char)?;
+
+        opponent_counts[opponent_move.0] += 1;
+        max_score += moves
+            .iter()
+            .map(|&m| get_round_score(rules, opponent_move, m))
+            .max()
+            .unwrap();
+        if get_round_outcome(rules, opponent_move, my_move) == Outcome::Lose {
+            lost_rounds.push(i + 1);
+        }
+    }
+
+    let opponent_frequencies = opponent_counts
+        .iter()
+        .map(|&count| count as f64 / rounds.len().max(1) as f64)
+        .collect_vec();
+    let expected_scores = moves
+        .iter()
+        .map(|&mine| {
+            moves
+                .iter()
+                .map(|&theirs| {
+                    opponent_frequencies[theirs.0] * get_round_score(rules, theirs, mine) as f64
+                })
+                .sum::<f64>()
+        })
+        .collect_vec();
+    let best_response = Move(
+        (0..n)
+            .max_by(|&i, &j| expected_scores[i].total_cmp(&expected_scores[j]))
+            .unwrap(),
+    );
+
+    // the rules are symmetric, so the opponent's score is get_round_score with the roles swapped
+    let payoffs = moves
+        .iter()
+        .map(|&mine| {
+            moves
+                .iter()
+                .map(|&theirs| {
+                    get_round_score(rules, theirs, mine) as f64
+                        - get_round_score(rules, mine, theirs) as f64
+                })
+                .collect_vec()
         })
         .collect_vec();
 
-    println!("Part 1 solution = {}", solve_part1(&rounds));
-    println!("Part 2 solution = {}", solve_part2(&rounds));
+    Ok(Analysis {
+        guide_score: solve_part1(rules, rounds)?,
+        max_score,
+        lost_rounds,
+        opponent_frequencies,
+        expected_scores,
+        best_response,
+        equilibrium: solve_zero_sum_game(&payoffs),
+    })
+}
+
+fn print_analysis(rules: &Rules, analysis: &Analysis) {
+    const MAX_LISTED: usize = 20;
+
+    let names = rules.moves.iter().map(|m| m.name.as_str()).collect_vec();
+    let percentages = |values: &[f64]| {
+        names
+            .iter()
+            .zip(values)
+            .map(|(name, v)| format!("{name} {:.1}%", v * 100.0))
+            .join(", ")
+    };
+
+    println!(
+        "Guide score = {}, maximum achievable = {}",
+        analysis.guide_score, analysis.max_score
+    );
+
+    let lost = &analysis.lost_rounds;
+    let mut listed = lost.iter().take(MAX_LISTED).join(", ");
+    if lost.len() > MAX_LISTED {
+        listed += ", ...";
+    }
+    println!("Guide loses {} rounds: {listed}", lost.len());
+
+    println!(
+        "Opponent plays {}",
+        percentages(&analysis.opponent_frequencies)
+    );
+    println!(
+        "Expected score per round: {}",
+        names
+            .iter()
+            .zip(&analysis.expected_scores)
+            .map(|(name, score)| format!("{name} {score:.3}"))
+            .join(", ")
+    );
+    println!(
+        "Best response to those frequencies = always play {}",
+        names[analysis.best_response.0]
+    );
+    println!(
+        "Minimax strategy = {}, guaranteeing an expected score margin of {:.3} per round",
+        percentages(&analysis.equilibrium.row_strategy),
+        analysis.equilibrium.value
+    );
+    println!(
+        "Opponent's minimax strategy = {}",
+        percentages(&analysis.equilibrium.col_strategy)
+    );
+}
+
+/// Usage: day2 [--rules FILE] [--analyze]
+fn main() -> Result<()> {
+    let mut rules_file = None;
+    let mut show_analysis = false;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rules" => {
+                let path = args.next().ok_or_else(|| anyhow!("--rules needs a file"))?;
+                rules_file = Some(read_to_string(path)?);
+            }
+            "--analyze" => show_analysis = true,
+            _ => bail!("Usage: day2 [--rules FILE] [--analyze]"),
+        }
+    }
+
+    let input = read_to_string("input/day2-input.txt")?;
+    let (header, rounds) = parse_input(&input)?;
+
+    let rules = match (header, rules_file.as_deref()) {
+        (Some(_), Some(_)) => bail!("Rules given both in the input and with --rules"),
+        (Some(text), None) | (None, Some(text)) => parse_rules(text)?,
+        (None, None) => parse_rules(DEFAULT_RULES)?,
+    };
+
+    println!("Part 1 solution = {}", solve_part1(&rules, &rounds)?);
+    println!("Part 2 solution = {}", solve_part2(&rules, &rounds)?);
+
+    if show_analysis {
+        print_analysis(&rules, &analyze(&rules, &rounds)?);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RPSLS: &str = "
+Rock      A X 1  D L W W L
+Paper     B Y 2  W D L L W
+Scissors  C Z 3  L W D W L
+Lizard    D V 4  L W L D W
+Spock     E W 5  W L W L D
+";
+
+    #[test]
+    fn test_default_rules() {
+        let rules = parse_rules(DEFAULT_RULES).unwrap();
+        let (header, rounds) = parse_input("A Y\nB X\nC Z\n").unwrap();
+        assert!(header.is_none());
+
+        assert_eq!(solve_part1(&rules, &rounds).unwrap(), 15);
+        assert_eq!(solve_part2(&rules, &rounds).unwrap(), 12);
+    }
+
+    #[test]
+    fn test_rpsls() {
+        let input = format!("[rules]{RPSLS}\nA V\nE X\nD Z\n");
+        let (header, rounds) = parse_input(&input).unwrap();
+        let rules = parse_rules(header.unwrap()).unwrap();
+
+        // lizard vs rock loses, rock vs spock loses, scissors vs lizard wins
+        assert_eq!(solve_part1(&rules, &rounds).unwrap(), 4 + 1 + 9);
+
+        // lose to rock with lizard, draw with spock, beat lizard with scissors rather than rock
+        let (_, rounds) = parse_input("A X\nE Y\nD Z\n").unwrap();
+        assert_eq!(solve_part2(&rules, &rounds).unwrap(), 4 + 8 + 9);
+        assert!(solve_part2(&rules, &[('A', 'V')]).is_err());
+        assert_eq!(rules.move_for_outcome(Move(0), Outcome::Lose), Move(3));
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let even = "Rock A X 1 D L\nPaper B Y 2 W D";
+        assert!(parse_rules(even).unwrap_err().to_string().contains("odd"));
+
+        let inconsistent = DEFAULT_RULES.replace("W D L", "W D W");
+        assert!(parse_rules(&inconsistent)
+            .unwrap_err()
+            .to_string()
+            .contains("Inconsistent"));
+
+        let unbalanced = RPSLS
+            .replace("D L W W L", "D W W W W")
+            .replace("W D L L W", "L D L L W")
+            .replace("L W D W L", "L W D W W")
+            .replace("L W L D W", "L W L D L")
+            .replace("W L W L D", "L L L W D");
+        assert!(parse_rules(&unbalanced)
+            .unwrap_err()
+            .to_string()
+            .contains("Unbalanced"));
+
+        let duplicate = DEFAULT_RULES.replace("B         Y", "A         Y");
+        assert!(parse_rules(&duplicate)
+            .unwrap_err()
+            .to_string()
+            .contains("Duplicate"));
+    }
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-6, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn test_zero_sum_game() {
+        let pennies = solve_zero_sum_game(&[vec![1.0, -1.0], vec![-1.0, 1.0]]);
+        assert_close(&[pennies.value], &[0.0]);
+        assert_close(&pennies.row_strategy, &[0.5, 0.5]);
+        assert_close(&pennies.col_strategy, &[0.5, 0.5]);
+
+        let skewed = solve_zero_sum_game(&[vec![3.0, -1.0], vec![-2.0, 1.0]]);
+        assert_close(&[skewed.value], &[1.0 / 7.0]);
+        assert_close(&skewed.row_strategy, &[3.0 / 7.0, 4.0 / 7.0]);
+        assert_close(&skewed.col_strategy, &[2.0 / 7.0, 5.0 / 7.0]);
+
+        // a dominated row gets no weight
+        let dominated = solve_zero_sum_game(&[vec![2.0, 2.0], vec![1.0, 0.0]]);
+        assert_close(&[dominated.value], &[2.0]);
+        assert_close(&dominated.row_strategy, &[1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_analysis() {
+        let rules = parse_rules(DEFAULT_RULES).unwrap();
+        let (_, rounds) = parse_input("A Y\nB X\nC Z\nA Z\n").unwrap();
+        let analysis = analyze(&rules, &rounds).unwrap();
+
+        assert_eq!(analysis.guide_score, 8 + 1 + 6 + 3);
+        assert_eq!(analysis.max_score, 8 + 9 + 7 + 8);
+        assert_eq!(analysis.lost_rounds, vec![2, 4]);
+        assert_close(&analysis.opponent_frequencies, &[0.5, 0.25, 0.25]);
+        assert_close(&analysis.expected_scores, &[4.0, 5.75, 5.25]);
+        assert_eq!(analysis.best_response, Move(1));
+
+        // the game is symmetric, so its value is 0.  Rock and scissors are played more because paper's extra
+        // point for playing it doesn't make up for losing the 6 points when it's beaten.
+        let equilibrium = &analysis.equilibrium;
+        assert_close(&[equilibrium.value], &[0.0]);
+        assert_close(
+            &equilibrium.row_strategy,
+            &[7.0 / 18.0, 4.0 / 18.0, 7.0 / 18.0],
+        );
+
+        // and no opponent move does better than the value against it
+        for theirs in (0..3).map(Move) {
+            let payoff: f64 = (0..3)
+                .map(Move)
+                .map(|mine| {
+                    let margin = get_round_score(&rules, theirs, mine) as f64
+                        - get_round_score(&rules, mine, theirs) as f64;
+                    equilibrium.row_strategy[mine.0] * margin
+                })
+                .sum();
+            assert!(payoff >= equilibrium.value - 1e-9, "{theirs:?}: {payoff}");
+        }
+    }
 }
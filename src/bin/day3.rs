@@ -1,58 +1,211 @@
 // https://adventofcode.com/2022/day/3
 
-use std::{collections::HashSet, fs::read_to_string};
+use std::{env, fmt, fs::read_to_string, ops::BitAnd};
 
+use anyhow::{anyhow, bail, Result};
 use itertools::Itertools;
 
-fn item_priority(item: char) -> usize {
-    if item.is_uppercase() {
-        item as usize - 'A' as usize + 27
+/// Items are a-z with priorities 1 through 26, and A-Z with priorities 27 through 52
+fn item_priority(item: char) -> Option<usize> {
+    match item {
+        'a'..='z' => Some(item as usize - 'a' as usize + 1),
+        'A'..='Z' => Some(item as usize - 'A' as usize + 27),
+        _ => None,
+    }
+}
+
+fn priority_item(priority: usize) -> char {
+    if priority <= 26 {
+        (b'a' + (priority - 1) as u8) as char
     } else {
-        item as usize - 'a' as usize + 1
+        (b'A' + (priority - 27) as u8) as char
+    }
+}
+
+/// A set of items, as a bitmask with bit n set if the item with priority n is present
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+struct ItemSet(u64);
+
+impl ItemSet {
+    fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    fn priorities(self) -> impl Iterator<Item = usize> {
+        (1..=52).filter(move |&p| self.0 & (1 << p) != 0)
+    }
+
+    /// The priority of the single item in the set, or None if it doesn't have exactly one
+    fn only_priority(self) -> Option<usize> {
+        (self.len() == 1).then(|| self.0.trailing_zeros() as usize)
     }
 }
 
-fn solve_part1(input: &String) -> usize {
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        ItemSet(self.0 & rhs.0)
+    }
+}
+
+impl TryFrom<&str> for ItemSet {
+    type Error = String;
+
+    fn try_from(items: &str) -> Result<Self, Self::Error> {
+        items.chars().try_fold(ItemSet::default(), |set, item| {
+            let priority = item_priority(item).ok_or_else(|| format!("Invalid item {item:?}"))?;
+            Ok(ItemSet(set.0 | 1 << priority))
+        })
+    }
+}
+
+impl fmt::Display for ItemSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{{}}}", self.priorities().map(priority_item).join(", "))
+    }
+}
+
+/// The priority of the only item in common, or an error describing what was found instead
+fn common_priority(sets: impl Iterator<Item = ItemSet>, what: &str) -> Result<usize> {
+    let common = sets.reduce(|s1, s2| s1 & s2).unwrap_or_default();
+    common.only_priority().ok_or_else(|| {
+        anyhow!(
+            "Expected exactly one item in {what}, found {}: {common}",
+            common.len()
+        )
+    })
+}
+
+fn solve_part1(input: &str) -> Result<usize> {
     input
         .lines()
-        .map(|line| {
-            assert_eq!(line.len() % 2, 0);
+        .enumerate()
+        .map(|(i, line)| {
+            let line_num = i + 1;
+            // split_at works on bytes, so anything that isn't an item has to be caught first
+            if let Some(item) = line.chars().find(|c| !c.is_ascii()) {
+                bail!("Line {line_num}: Invalid item {item:?}");
+            }
+            if !line.len().is_multiple_of(2) {
+                bail!("Line {line_num} has an odd number of items");
+            }
 
             let (compartment1, compartment2) = line.split_at(line.len() / 2);
+            let sets = [compartment1, compartment2]
+                .into_iter()
+                .map(ItemSet::try_from)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| anyhow!("Line {line_num}: {e}"))?;
 
-            // use hashset intersection to find common element
-            let in_both: HashSet<char> = &compartment1.chars().collect::<HashSet<char>>()
-                & &compartment2.chars().collect::<HashSet<char>>();
-            let in_both = *in_both.iter().exactly_one().unwrap();
-
-            item_priority(in_both)
+            common_priority(
+                sets.into_iter(),
+                &format!("both compartments on line {line_num}"),
+            )
         })
         .sum()
 }
 
-fn solve_part2(input: &String) -> usize {
-    input
-        .lines()
-        .chunks(3)
-        .into_iter()
-        .map(|group| {
+fn solve_part2(input: &str, group_size: usize) -> Result<usize> {
+    let lines = input.lines().collect_vec();
+    if !lines.len().is_multiple_of(group_size) {
+        bail!(
+            "{} lines can't be split into groups of {group_size}",
+            lines.len()
+        );
+    }
+
+    lines
+        .chunks(group_size)
+        .enumerate()
+        .map(|(i, group)| {
             // Find the intersection of all lines in group
-            let badge = *group
-                .map(|line| line.chars().collect::<HashSet<char>>())
-                .reduce(|s1, s2| &s1 & &s2)
-                .unwrap()
+            let first_line = i * group_size + 1;
+            let sets = group
                 .iter()
-                .exactly_one()
-                .unwrap();
+                .enumerate()
+                .map(|(j, &line)| {
+                    ItemSet::try_from(line).map_err(|e| anyhow!("Line {}: {e}", first_line + j))
+                })
+                .collect::<Result<Vec<_>>>()?;
 
-            item_priority(badge)
+            let lines = format!(
+                "the group on lines {first_line}-{}",
+                first_line + group_size - 1
+            );
+            common_priority(sets.into_iter(), &lines)
         })
         .sum()
 }
 
-fn main() {
-    let input = read_to_string("input/day3-input.txt").unwrap();
+/// Usage: day3 [--group-size N]
+fn main() -> Result<()> {
+    let group_size = match env::args().skip(1).collect_vec()[..] {
+        [] => 3,
+        [ref flag, ref n] if flag == "--group-size" => n
+            .parse()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or_else(|| anyhow!("--group-size must be a positive integer, got {n:?}"))?,
+        _ => bail!("Usage: day3 [--group-size N]"),
+    };
+
+    let input = read_to_string("input/day3-input.txt")?;
+
+    println!("Part 1 solution = {}", solve_part1(&input)?);
+    println!("Part 2 solution = {}", solve_part2(&input, group_size)?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
 
-    println!("Part 1 solution = {}", solve_part1(&input));
-    println!("Part 2 solution = {}", solve_part2(&input));
+    #[test]
+    fn test_item_set() {
+        let set = ItemSet::try_from("aAzZa").unwrap();
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.priorities().collect_vec(), vec![1, 26, 27, 52]);
+        assert_eq!(set.to_string(), "{a, z, A, Z}");
+        assert_eq!(
+            (set & ItemSet::try_from("Zq").unwrap()).only_priority(),
+            Some(52)
+        );
+        assert!(ItemSet::try_from("ab1").is_err());
+    }
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(solve_part1(EXAMPLE).unwrap(), 157);
+        assert_eq!(solve_part2(EXAMPLE, 3).unwrap(), 70);
+        let err = solve_part2(EXAMPLE, 1).unwrap_err();
+        assert!(err.to_string().contains("group on lines 1-1"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = solve_part1("abca\nabcd\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Expected exactly one item in both compartments on line 2, found 0: {}"
+        );
+        assert!(solve_part1("aa\nabc\n")
+            .unwrap_err()
+            .to_string()
+            .contains("Line 2"));
+        assert_eq!(
+            solve_part1("aa\naéb\n").unwrap_err().to_string(),
+            "Line 2: Invalid item 'é'"
+        );
+        assert!(solve_part2(EXAMPLE, 4).is_err());
+    }
 }
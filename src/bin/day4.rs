@@ -4,7 +4,12 @@ use std::fs::read_to_string;
 
 use itertools::Itertools;
 
-fn parse_input(input: &String) -> Vec<((usize, usize), (usize, usize))> {
+use common::interval::{Interval, IntervalSet, Relation};
+
+/// The section ranges assigned to a pair of elves
+type RangePair = ((usize, usize), (usize, usize));
+
+fn parse_input(input: &str) -> Vec<RangePair> {
     // each line looks like "2-4,6-8"
     input
         .lines()
@@ -19,6 +24,70 @@ fn parse_input(input: &String) -> Vec<((usize, usize), (usize, usize))> {
         .collect_vec()
 }
 
+/// The relations that remain when each relation and its converse are counted together, e.g. "contains" as
+/// "during" with the pair swapped
+const BASE_RELATIONS: [Relation; 7] = [
+    Relation::Before,
+    Relation::Meets,
+    Relation::Overlaps,
+    Relation::Starts,
+    Relation::During,
+    Relation::Finishes,
+    Relation::Equals,
+];
+
+/// Count the pairs by how their ranges relate, ignoring which elf of the pair has which range
+fn relation_counts(range_pairs: &[RangePair]) -> [(Relation, usize); 7] {
+    let mut counts = BASE_RELATIONS.map(|relation| (relation, 0));
+
+    for &(range1, range2) in range_pairs {
+        let relation = Interval::from(range1).relation(Interval::from(range2));
+        let base = if BASE_RELATIONS.contains(&relation) {
+            relation
+        } else {
+            relation.converse()
+        };
+        counts.iter_mut().find(|(r, _)| *r == base).unwrap().1 += 1;
+    }
+
+    counts
+}
+
+/// The largest number of ranges sharing a section, and the first section where that happens.  Sweeps over
+/// the range ends in order, so takes time proportional to the number of ranges rather than sections.
+fn max_overlap(range_pairs: &[RangePair]) -> Option<(usize, usize)> {
+    // each range start..=end adds one at start and removes one after end, and removals sort first
+    let events = range_pairs
+        .iter()
+        .flat_map(|&(range1, range2)| [range1, range2])
+        .flat_map(|(start, end)| [(start, 1), (end + 1, -1)])
+        .sorted();
+
+    let mut active = 0isize;
+    let mut best: Option<(usize, usize)> = None;
+    for (section, change) in events {
+        active += change;
+        if best.is_none_or(|(count, _)| active as usize > count) {
+            best = Some((active as usize, section));
+        }
+    }
+
+    best
+}
+
+/// The sections from 1 up to the last assigned section that aren't in any range
+fn unassigned_sections(range_pairs: &[RangePair]) -> IntervalSet<usize> {
+    let assigned = range_pairs
+        .iter()
+        .flat_map(|&(range1, range2)| [Interval::from(range1), Interval::from(range2)])
+        .collect::<IntervalSet<_>>();
+
+    match assigned.bounds() {
+        Some(bounds) if bounds.end() >= 1 => assigned.complement(Interval::new(1, bounds.end())),
+        _ => IntervalSet::new(),
+    }
+}
+
 fn main() {
     let input = read_to_string("input/day4-input.txt").unwrap();
     let range_pairs = parse_input(&input);
@@ -26,8 +95,9 @@ fn main() {
     // Count how many ranges are completely contained in their companion range
     let part1_result = range_pairs
         .iter()
-        .filter(|((start1, end1), (start2, end2))| {
-            (start1 >= start2 && end1 <= end2) || (start2 >= start1 && end2 <= end1)
+        .filter(|&&(range1, range2)| {
+            let (range1, range2) = (Interval::from(range1), Interval::from(range2));
+            range1.contains_interval(range2) || range2.contains_interval(range1)
         })
         .count();
 
@@ -36,10 +106,76 @@ fn main() {
     // Count how many ranges overlap their companion range
     let part2_result = range_pairs
         .iter()
-        .filter(|((start1, end1), (start2, end2))| {
-            (start1..=end1).contains(&start2) || (start2..=end2).contains(&start1)
-        })
+        .filter(|&&(range1, range2)| Interval::from(range1).overlaps(Interval::from(range2)))
         .count();
 
     println!("Part 2 solution = {}", part2_result);
+
+    for (relation, count) in relation_counts(&range_pairs) {
+        println!("{relation:?}: {count}");
+    }
+
+    if let Some((count, section)) = max_overlap(&range_pairs) {
+        println!("At most {count} assignments overlap, first at section {section}");
+    }
+
+    let unassigned = unassigned_sections(&range_pairs);
+    println!(
+        "{} unassigned sections: {}",
+        unassigned.covered_len(),
+        unassigned.iter().join(", ")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+";
+
+    #[test]
+    fn test_relation_counts() {
+        let range_pairs = parse_input(EXAMPLE);
+        assert_eq!(
+            relation_counts(&range_pairs),
+            [
+                (Relation::Before, 1),
+                (Relation::Meets, 1),
+                (Relation::Overlaps, 2),
+                (Relation::Starts, 0),
+                (Relation::During, 1),
+                (Relation::Finishes, 1),
+                (Relation::Equals, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_overlap_and_gaps() {
+        let range_pairs = parse_input(EXAMPLE);
+        assert_eq!(max_overlap(&range_pairs), Some((8, 6)));
+        assert_eq!(max_overlap(&[]), None);
+
+        // ranges that only touch at an end don't overlap
+        assert_eq!(max_overlap(&[((1, 3), (4, 6))]), Some((1, 1)));
+
+        assert_eq!(
+            unassigned_sections(&range_pairs).intervals(),
+            &[Interval::new(1, 1)]
+        );
+        assert_eq!(
+            unassigned_sections(&[((3, 4), (8, 10)), ((9, 12), (14, 14))]).intervals(),
+            &[
+                Interval::new(1, 2),
+                Interval::new(5, 7),
+                Interval::new(13, 13)
+            ]
+        );
+    }
 }
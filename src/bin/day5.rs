@@ -1,38 +1,105 @@
 // https://adventofcode.com/2022/day/5
 
-use std::fs::read_to_string;
+use std::{env, fmt, fs::read_to_string};
 
+use anyhow::{anyhow, bail, Result};
 use itertools::Itertools;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct Step {
     num_to_move: usize,
     from_stack: usize,
     to_stack: usize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 struct Problem {
     stacks: Vec<Vec<char>>,
     steps: Vec<Step>,
 }
 
-fn parse_input(input: &String) -> Problem {
-    let (stacks_str, steps_str) = input.split("\n\n").collect_tuple().unwrap();
+/// Written the way the input writes steps, with 1-based stack numbers
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.num_to_move,
+            self.from_stack + 1,
+            self.to_stack + 1
+        )
+    }
+}
+
+/// Draw the stacks as in the input: one row of `[X]` cells per level, then the stack numbers centered under
+/// them.  Every line is padded to the full width, like the puzzle input.
+fn render_stacks(stacks: &[Vec<char>]) -> String {
+    let height = stacks.iter().map(|stack| stack.len()).max().unwrap_or(0);
+
+    let mut lines = (0..height)
+        .rev()
+        .map(|level| {
+            stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(crate_char) => format!("[{crate_char}]"),
+                    None => "   ".to_string(),
+                })
+                .join(" ")
+        })
+        .collect_vec();
+    lines.push((1..=stacks.len()).map(|n| format!("{n:^3}")).join(" "));
+
+    lines.join("\n")
+}
+
+/// Written in the same format as the input, so it can be parsed back
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}\n", render_stacks(&self.stacks))?;
+        for step in &self.steps {
+            writeln!(f, "{step}")?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_input(input: &str) -> Result<Problem> {
+    let (stacks_str, steps_str) = input
+        .split_once("\n\n")
+        .ok_or_else(|| anyhow!("Expected a blank line between the stacks and the steps"))?;
 
     let stacks_lines = stacks_str.lines().collect_vec();
 
-    // last line of stacks section is the stack numbers
-    let num_stacks = stacks_lines[stacks_lines.len() - 1]
-        .split_ascii_whitespace()
-        .count();
+    // last line of stacks section is the stack numbers, which should be 1, 2, 3...
+    let (numbers_line, crate_lines) = stacks_lines
+        .split_last()
+        .ok_or_else(|| anyhow!("Missing stack diagram"))?;
+    let num_stacks = numbers_line.split_ascii_whitespace().count();
+    for (i, num_str) in numbers_line.split_ascii_whitespace().enumerate() {
+        if num_str.parse::<usize>().ok() != Some(i + 1) {
+            bail!(
+                "Line {}: expected stack number {}, found {num_str:?}",
+                stacks_lines.len(),
+                i + 1
+            );
+        }
+    }
 
     let mut stacks: Vec<Vec<char>> = vec![vec![]; num_stacks];
-    for line in stacks_lines[0..stacks_lines.len() - 1].into_iter() {
+    for (line_index, line) in crate_lines.iter().enumerate() {
         // each line looks like "[T]     [H]     [V] [Q]         [H]"
         for (stack_num, crate_char) in line.chars().skip(1).step_by(4).enumerate() {
             if crate_char != ' ' {
-                stacks[stack_num].push(crate_char);
+                stacks
+                    .get_mut(stack_num)
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Line {}: crate {crate_char:?} is beyond the last stack",
+                            line_index + 1
+                        )
+                    })?
+                    .push(crate_char);
             }
         }
     }
@@ -42,48 +109,724 @@ fn parse_input(input: &String) -> Problem {
         stack.reverse();
     }
 
+    let first_step_line = stacks_lines.len() + 2;
     let steps = steps_str
         .lines()
-        .map(|line| {
+        .enumerate()
+        .map(|(i, line)| {
             // each line looks like "move 1 from 8 to 7"
-            let parts = line.split_ascii_whitespace().collect_vec();
+            let parse_step = || {
+                let (num_to_move, from_stack, to_stack) =
+                    match line.split_ascii_whitespace().collect_vec()[..] {
+                        ["move", n, "from", from, "to", to] => (
+                            n.parse().ok()?,
+                            from.parse::<usize>().ok()?,
+                            to.parse::<usize>().ok()?,
+                        ),
+                        _ => return None,
+                    };
+                Some(Step {
+                    num_to_move,
+                    from_stack: from_stack.checked_sub(1)?,
+                    to_stack: to_stack.checked_sub(1)?,
+                })
+            };
 
-            Step {
-                num_to_move: parts[1].parse::<usize>().unwrap(),
-                from_stack: parts[3].parse::<usize>().unwrap() - 1,
-                to_stack: parts[5].parse::<usize>().unwrap() - 1,
-            }
+            parse_step()
+                .ok_or_else(|| anyhow!("Line {}: invalid step {line:?}", first_step_line + i))
         })
-        .collect_vec();
+        .collect::<Result<Vec<_>>>()?;
 
-    Problem { stacks, steps }
+    Ok(Problem { stacks, steps })
 }
 
-fn solve(problem: &Problem, at_once: bool) -> String {
-    let mut stacks = problem.stacks.clone();
+/// Check that every step can be carried out, by tracking the stack sizes (which don't depend on the crane).
+/// Errors give the 1-based step number and the stack sizes before the step.
+fn validate(problem: &Problem) -> Result<()> {
+    let mut sizes = problem.stacks.iter().map(|stack| stack.len()).collect_vec();
+
+    for (i, step) in problem.steps.iter().enumerate() {
+        let describe = || format!("Step {} ({step}), with stack sizes {sizes:?}", i + 1);
+
+        for stack in [step.from_stack, step.to_stack] {
+            if stack >= sizes.len() {
+                bail!(
+                    "{}: stack {} doesn't exist, there are {} stacks",
+                    describe(),
+                    stack + 1,
+                    sizes.len()
+                );
+            }
+        }
+        if step.num_to_move > sizes[step.from_stack] {
+            bail!(
+                "{}: can't move {} crates from a stack of {}",
+                describe(),
+                step.num_to_move,
+                sizes[step.from_stack]
+            );
+        }
+
+        sizes[step.from_stack] -= step.num_to_move;
+        sizes[step.to_stack] += step.num_to_move;
+    }
+
+    Ok(())
+}
+
+/// A small seeded PRNG (SplitMix64), so random crane behaviour is reproducible without extra dependencies
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in 0..n.  The modulo bias is negligible for the small n used here.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// A model of how a crane carries out a `Step`
+trait Crane {
+    fn name(&self) -> String;
+
+    /// Move the crates for step, returning the number of lifts it took
+    fn execute(&mut self, stacks: &mut [Vec<char>], step: &Step) -> usize;
+}
+
+/// Moves one crate at a time, so the moved crates end up in reverse order
+struct CrateMover9000;
+
+/// Moves all the crates in one lift, keeping their order
+struct CrateMover9001;
+
+/// Lifts at most capacity crates at a time, keeping the order within each lift
+struct CapacityLimited {
+    capacity: usize,
+}
+
+impl CapacityLimited {
+    fn new(capacity: usize) -> CapacityLimited {
+        assert!(
+            capacity > 0,
+            "a crane has to be able to lift at least one crate"
+        );
+        CapacityLimited { capacity }
+    }
+}
+
+/// Moves all the crates in one lift, but sets them down in a random order
+struct Shuffling {
+    seed: u64,
+    rng: SplitMix64,
+}
+
+impl Shuffling {
+    fn new(seed: u64) -> Shuffling {
+        Shuffling {
+            seed,
+            rng: SplitMix64(seed),
+        }
+    }
+}
+
+/// Pulls crates out from the bottom of the stack one at a time, putting each on top of the destination
+struct BottomPick;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+
+    fn execute(&mut self, stacks: &mut [Vec<char>], step: &Step) -> usize {
+        CapacityLimited::new(1).execute(stacks, step)
+    }
+}
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+
+    fn execute(&mut self, stacks: &mut [Vec<char>], step: &Step) -> usize {
+        let from_stack = &mut stacks[step.from_stack];
+        let crates_to_move = from_stack.split_off(from_stack.len() - step.num_to_move);
+        stacks[step.to_stack].extend_from_slice(&crates_to_move);
+
+        1
+    }
+}
+
+impl Crane for CapacityLimited {
+    fn name(&self) -> String {
+        format!("Capacity {}", self.capacity)
+    }
+
+    fn execute(&mut self, stacks: &mut [Vec<char>], step: &Step) -> usize {
+        let mut remaining = step.num_to_move;
+        let mut lifts = 0;
+
+        while remaining > 0 {
+            let lift = remaining.min(self.capacity);
+            CrateMover9001.execute(
+                stacks,
+                &Step {
+                    num_to_move: lift,
+                    ..*step
+                },
+            );
+            remaining -= lift;
+            lifts += 1;
+        }
+
+        lifts
+    }
+}
 
-    for step in problem.steps.iter() {
+impl Crane for Shuffling {
+    fn name(&self) -> String {
+        format!("Shuffling (seed {})", self.seed)
+    }
+
+    fn execute(&mut self, stacks: &mut [Vec<char>], step: &Step) -> usize {
         let from_stack = &mut stacks[step.from_stack];
         let mut crates_to_move = from_stack.split_off(from_stack.len() - step.num_to_move);
 
-        if !at_once {
-            // simulate moving crates one-at-a-time
-            crates_to_move.reverse();
+        // Fisher-Yates
+        for i in (1..crates_to_move.len()).rev() {
+            crates_to_move.swap(i, self.rng.below(i + 1));
         }
 
         stacks[step.to_stack].extend_from_slice(&crates_to_move);
+
+        1
+    }
+}
+
+impl Crane for BottomPick {
+    fn name(&self) -> String {
+        "Bottom pick".to_string()
+    }
+
+    fn execute(&mut self, stacks: &mut [Vec<char>], step: &Step) -> usize {
+        let crates_to_move = stacks[step.from_stack]
+            .drain(..step.num_to_move)
+            .collect_vec();
+        stacks[step.to_stack].extend_from_slice(&crates_to_move);
+
+        step.num_to_move
+    }
+}
+
+/// A crane whose steps can be undone: the arrangement after a step determines the one before it.  (Shuffling
+/// isn't reversible without remembering the shuffles.)
+trait ReversibleCrane: Crane {
+    /// Restore the stacks to how they were before `execute(stacks, step)`
+    fn undo(&mut self, stacks: &mut [Vec<char>], step: &Step);
+}
+
+impl Step {
+    /// The same number of crates moved back the other way
+    fn reversed(&self) -> Step {
+        Step {
+            num_to_move: self.num_to_move,
+            from_stack: self.to_stack,
+            to_stack: self.from_stack,
+        }
+    }
+}
+
+impl ReversibleCrane for CrateMover9000 {
+    fn undo(&mut self, stacks: &mut [Vec<char>], step: &Step) {
+        // reversing the order twice restores it
+        self.execute(stacks, &step.reversed());
+    }
+}
+
+impl ReversibleCrane for CrateMover9001 {
+    fn undo(&mut self, stacks: &mut [Vec<char>], step: &Step) {
+        self.execute(stacks, &step.reversed());
+    }
+}
+
+impl ReversibleCrane for CapacityLimited {
+    fn undo(&mut self, stacks: &mut [Vec<char>], step: &Step) {
+        // the lifts were full ones followed by a partial one, and are moved back last lift first
+        let full_lifts = step.num_to_move / self.capacity;
+        let partial_lift = step.num_to_move % self.capacity;
+        let lifts =
+            std::iter::once(partial_lift).chain(std::iter::repeat_n(self.capacity, full_lifts));
+
+        for num_to_move in lifts.filter(|&n| n > 0) {
+            CrateMover9001.execute(
+                stacks,
+                &Step {
+                    num_to_move,
+                    ..step.reversed()
+                },
+            );
+        }
+    }
+}
+
+impl ReversibleCrane for BottomPick {
+    fn undo(&mut self, stacks: &mut [Vec<char>], step: &Step) {
+        let to_stack = &mut stacks[step.to_stack];
+        let crates_to_move = to_stack.split_off(to_stack.len() - step.num_to_move);
+        stacks[step.from_stack].splice(0..0, crates_to_move);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct CraneResult {
+    /// the top crate of each stack
+    tops: String,
+    /// the total number of lifts the crane made
+    lifts: usize,
+}
+
+/// The stacks after the first num_steps steps, and the number of lifts that took
+fn run_steps(
+    problem: &Problem,
+    crane: &mut dyn Crane,
+    num_steps: usize,
+) -> Result<(Vec<Vec<char>>, usize)> {
+    validate(problem)?;
+    if num_steps > problem.steps.len() {
+        bail!(
+            "Can't run {num_steps} steps, there are only {}",
+            problem.steps.len()
+        );
+    }
+
+    let mut stacks = problem.stacks.clone();
+    let mut lifts = 0;
+
+    for step in &problem.steps[..num_steps] {
+        lifts += crane.execute(&mut stacks, step);
     }
 
-    stacks
+    Ok((stacks, lifts))
+}
+
+fn solve(problem: &Problem, crane: &mut dyn Crane) -> Result<CraneResult> {
+    let (stacks, lifts) = run_steps(problem, crane, problem.steps.len())?;
+
+    // empty stacks have no top crate, so show them as a space
+    let tops = stacks
         .into_iter()
-        .map(|stack| stack[stack.len() - 1])
-        .collect()
+        .map(|stack| stack.last().copied().unwrap_or(' '))
+        .collect();
+
+    Ok(CraneResult { tops, lifts })
+}
+
+/// Work backwards from the stacks after all the steps to the starting stacks, checking that each step can be
+/// undone.  Errors give the 1-based step number and the stack sizes after the step.
+fn reconstruct_start(
+    final_stacks: &[Vec<char>],
+    steps: &[Step],
+    crane: &mut dyn ReversibleCrane,
+) -> Result<Vec<Vec<char>>> {
+    let mut stacks = final_stacks.to_vec();
+
+    for (i, step) in steps.iter().enumerate().rev() {
+        let sizes = stacks.iter().map(|stack| stack.len()).collect_vec();
+        let describe = || {
+            format!(
+                "Undoing step {} ({step}), with stack sizes {sizes:?}",
+                i + 1
+            )
+        };
+
+        for stack in [step.from_stack, step.to_stack] {
+            if stack >= sizes.len() {
+                bail!(
+                    "{}: stack {} doesn't exist, there are {} stacks",
+                    describe(),
+                    stack + 1,
+                    sizes.len()
+                );
+            }
+        }
+        if step.num_to_move > sizes[step.to_stack] {
+            bail!(
+                "{}: can't take back {} crates from a stack of {}",
+                describe(),
+                step.num_to_move,
+                sizes[step.to_stack]
+            );
+        }
+
+        crane.undo(&mut stacks, step);
+    }
+
+    Ok(stacks)
+}
+
+/// Usage: day5 [--after N] [--reverse]
+fn main() -> Result<()> {
+    let mut show_after = None;
+    let mut show_reversed = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--after" => {
+                let n = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--after needs a step number"))?;
+                show_after = Some(
+                    n.parse::<usize>()
+                        .map_err(|_| anyhow!("--after needs a step number, got {n:?}"))?,
+                );
+            }
+            "--reverse" => show_reversed = true,
+            _ => bail!("Usage: day5 [--after N] [--reverse]"),
+        }
+    }
+
+    let input = read_to_string("input/day5-input.txt")?;
+
+    let problem = parse_input(&input)?;
+    println!(
+        "Part 1 solution = {}",
+        solve(&problem, &mut CrateMover9000)?.tops
+    );
+    println!(
+        "Part 2 solution = {}",
+        solve(&problem, &mut CrateMover9001)?.tops
+    );
+
+    let cranes: Vec<Box<dyn Crane>> = vec![
+        Box::new(CrateMover9000),
+        Box::new(CrateMover9001),
+        Box::new(CapacityLimited::new(3)),
+        Box::new(Shuffling::new(2022)),
+        Box::new(BottomPick),
+    ];
+    // every crane moves the same crates, they just differ in how many lifts that takes
+    println!(
+        "\n{} crates moved in total",
+        problem
+            .steps
+            .iter()
+            .map(|step| step.num_to_move)
+            .sum::<usize>()
+    );
+    for mut crane in cranes {
+        let result = solve(&problem, crane.as_mut())?;
+        println!(
+            "{:<22} tops = {}, lifts = {}",
+            crane.name(),
+            result.tops,
+            result.lifts
+        );
+    }
+
+    if let Some(num_steps) = show_after {
+        for mut crane in [
+            Box::new(CrateMover9000) as Box<dyn Crane>,
+            Box::new(CrateMover9001),
+        ] {
+            let (stacks, _) = run_steps(&problem, crane.as_mut(), num_steps)?;
+            println!(
+                "\n{} after {num_steps} steps:\n{}",
+                crane.name(),
+                render_stacks(&stacks)
+            );
+        }
+    }
+
+    if show_reversed {
+        // working back from where each crane finished should get back to the starting diagram
+        for mut crane in [
+            Box::new(CrateMover9000) as Box<dyn ReversibleCrane>,
+            Box::new(CrateMover9001),
+        ] {
+            let (final_stacks, _) = run_steps(&problem, crane.as_mut(), problem.steps.len())?;
+            let start = reconstruct_start(&final_stacks, &problem.steps, crane.as_mut())?;
+            println!(
+                "\nStart reconstructed from the {} result:\n{}",
+                crane.name(),
+                render_stacks(&start)
+            );
+        }
+    }
+
+    Ok(())
 }
 
-fn main() {
-    let input = read_to_string("input/day5-input.txt").unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+    fn result(tops: &str, lifts: usize) -> CraneResult {
+        CraneResult {
+            tops: tops.to_string(),
+            lifts,
+        }
+    }
+
+    #[test]
+    fn test_crate_movers() {
+        let problem = parse_input(EXAMPLE).unwrap();
+        assert_eq!(
+            solve(&problem, &mut CrateMover9000).unwrap(),
+            result("CMZ", 7)
+        );
+        assert_eq!(
+            solve(&problem, &mut CrateMover9001).unwrap(),
+            result("MCD", 4)
+        );
+    }
+
+    #[test]
+    fn test_other_cranes() {
+        let problem = parse_input(EXAMPLE).unwrap();
+
+        // capacity 1 is a CrateMover 9000, and a capacity as large as any move is a 9001
+        assert_eq!(
+            solve(&problem, &mut CapacityLimited::new(1)).unwrap(),
+            result("CMZ", 7)
+        );
+        assert_eq!(
+            solve(&problem, &mut CapacityLimited::new(3)).unwrap(),
+            result("MCD", 4)
+        );
+        assert_eq!(
+            solve(&problem, &mut CapacityLimited::new(2)).unwrap(),
+            result("MCZ", 5)
+        );
+
+        assert_eq!(solve(&problem, &mut BottomPick).unwrap(), result("DCM", 7));
+
+        // the same seed always gives the same result, and shuffling doesn't lose crates
+        let shuffled = solve(&problem, &mut Shuffling::new(7)).unwrap();
+        assert_eq!(shuffled, solve(&problem, &mut Shuffling::new(7)).unwrap());
+        assert_eq!(shuffled.lifts, 4);
+
+        let mut stacks = problem.stacks.clone();
+        let mut crane = Shuffling::new(7);
+        for step in &problem.steps {
+            crane.execute(&mut stacks, step);
+        }
+        assert_eq!(
+            stacks.concat().into_iter().sorted().collect::<String>(),
+            "CDMNPZ"
+        );
+    }
+
+    #[test]
+    fn test_validation() {
+        let mut problem = parse_input(EXAMPLE).unwrap();
+        assert!(validate(&problem).is_ok());
+
+        problem.steps[1].num_to_move = 4;
+        assert_eq!(
+            solve(&problem, &mut CrateMover9000).unwrap_err().to_string(),
+            "Step 2 (move 4 from 1 to 3), with stack sizes [3, 2, 1]: can't move 4 crates from a stack of 3"
+        );
+
+        problem.steps[1].num_to_move = 3;
+        problem.steps[3].to_stack = 3;
+        assert_eq!(
+            validate(&problem).unwrap_err().to_string(),
+            "Step 4 (move 1 from 1 to 4), with stack sizes [2, 0, 4]: stack 4 doesn't exist, there are 3 stacks"
+        );
+
+        // emptying a stack leaves a space in the tops
+        problem.steps[3] = Step {
+            num_to_move: 2,
+            from_stack: 0,
+            to_stack: 1,
+        };
+        assert_eq!(solve(&problem, &mut CrateMover9000).unwrap().tops, " CZ");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |input: String| parse_input(&input).unwrap_err().to_string();
+
+        assert_eq!(
+            err(EXAMPLE.replace("move 2 from 2", "move two from 2")),
+            "Line 8: invalid step \"move two from 2 to 1\""
+        );
+        assert_eq!(
+            err(EXAMPLE.replace("move 1 from 2", "move 1 from 0")),
+            "Line 6: invalid step \"move 1 from 0 to 1\""
+        );
+        assert_eq!(
+            err(EXAMPLE.replace(" 3 ", " 4 ")),
+            "Line 4: expected stack number 3, found \"4\""
+        );
+        assert!(err(EXAMPLE.replace("\n\n", "\n")).contains("blank line"));
+    }
+
+    #[test]
+    fn test_render_round_trip() {
+        let problem = parse_input(EXAMPLE).unwrap();
+        assert_eq!(problem.to_string(), EXAMPLE);
+
+        // editors often strip trailing whitespace, which mustn't change the meaning
+        let stripped = EXAMPLE.lines().map(|line| line.trim_end()).join("\n");
+        assert_eq!(parse_input(&stripped).unwrap(), problem);
+    }
+
+    #[test]
+    fn test_render_wide() {
+        // more than 9 stacks, with some empty and some tall
+        let stacks = (0..12)
+            .map(|i| {
+                (0..i % 5)
+                    .map(|j| (b'A' + (i + j) as u8) as char)
+                    .collect_vec()
+            })
+            .collect_vec();
+        let steps = vec![
+            Step {
+                num_to_move: 2,
+                from_stack: 11,
+                to_stack: 9,
+            },
+            Step {
+                num_to_move: 3,
+                from_stack: 9,
+                to_stack: 0,
+            },
+        ];
+        let problem = Problem { stacks, steps };
+
+        let rendered = problem.to_string();
+        assert!(rendered.contains(" 9  10  11  12 \n\nmove 2 from 12 to 10\n"));
+        assert_eq!(parse_input(&rendered).unwrap(), problem);
+
+        let stripped = rendered.lines().map(|line| line.trim_end()).join("\n");
+        assert_eq!(parse_input(&stripped).unwrap(), problem);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one crate")]
+    fn test_zero_capacity() {
+        CapacityLimited::new(0);
+    }
+
+    #[test]
+    fn test_state_after_steps() {
+        let problem = parse_input(EXAMPLE).unwrap();
+
+        let (stacks, lifts) = run_steps(&problem, &mut CrateMover9000, 2).unwrap();
+        assert_eq!(lifts, 4);
+        assert_eq!(
+            render_stacks(&stacks),
+            "        [Z]\n        [N]\n    [C] [D]\n    [M] [P]\n 1   2   3 "
+        );
+
+        let (stacks, _) = run_steps(&problem, &mut CrateMover9000, 0).unwrap();
+        assert_eq!(stacks, problem.stacks);
+        assert!(run_steps(&problem, &mut CrateMover9000, 5).is_err());
+    }
+
+    /// A random problem whose steps are all valid
+    fn random_problem(rng: &mut SplitMix64) -> Problem {
+        let num_stacks = 1 + rng.below(12);
+        let mut stacks = (0..num_stacks)
+            .map(|_| {
+                (0..rng.below(6))
+                    .map(|_| (b'A' + rng.below(26) as u8) as char)
+                    .collect_vec()
+            })
+            .collect_vec();
+        if stacks.iter().all(|stack| stack.is_empty()) {
+            stacks[0].push('X');
+        }
 
-    let problem = parse_input(&input);
-    println!("Part 1 solution = {}", solve(&problem, false));
-    println!("Part 2 solution = {}", solve(&problem, true));
+        let mut sizes = stacks.iter().map(|stack| stack.len()).collect_vec();
+        let steps = (0..rng.below(30))
+            .map(|_| {
+                let non_empty = (0..num_stacks).filter(|&i| sizes[i] > 0).collect_vec();
+                let from_stack = non_empty[rng.below(non_empty.len())];
+                let to_stack = rng.below(num_stacks);
+                let num_to_move = 1 + rng.below(sizes[from_stack]);
+                sizes[from_stack] -= num_to_move;
+                sizes[to_stack] += num_to_move;
+                Step {
+                    num_to_move,
+                    from_stack,
+                    to_stack,
+                }
+            })
+            .collect_vec();
+
+        Problem { stacks, steps }
+    }
+
+    #[test]
+    fn test_forward_then_reverse() {
+        let mut rng = SplitMix64(5);
+        let mut cranes: Vec<Box<dyn ReversibleCrane>> = vec![
+            Box::new(CrateMover9000),
+            Box::new(CrateMover9001),
+            Box::new(CapacityLimited::new(2)),
+            Box::new(CapacityLimited::new(3)),
+            Box::new(BottomPick),
+        ];
+
+        for _ in 0..200 {
+            let problem = random_problem(&mut rng);
+            assert!(validate(&problem).is_ok());
+
+            for crane in cranes.iter_mut() {
+                let crane = crane.as_mut();
+                let (final_stacks, _) = run_steps(&problem, crane, problem.steps.len()).unwrap();
+                assert_eq!(
+                    reconstruct_start(&final_stacks, &problem.steps, crane).unwrap(),
+                    problem.stacks,
+                    "{}",
+                    crane.name()
+                );
+
+                // and undoing each step right after doing it
+                let mut stacks = problem.stacks.clone();
+                for step in &problem.steps {
+                    let before = stacks.clone();
+                    crane.execute(&mut stacks, step);
+                    let after = stacks.clone();
+                    crane.undo(&mut stacks, step);
+                    assert_eq!(stacks, before, "{}: {step}", crane.name());
+                    stacks = after;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reconstruct_errors() {
+        let problem = parse_input(EXAMPLE).unwrap();
+        let (mut final_stacks, _) = run_steps(&problem, &mut CrateMover9001, 4).unwrap();
+        final_stacks[1].clear();
+
+        assert_eq!(
+            reconstruct_start(&final_stacks, &problem.steps, &mut CrateMover9001)
+                .unwrap_err()
+                .to_string(),
+            "Undoing step 4 (move 1 from 1 to 2), with stack sizes [1, 0, 4]: can't take back 1 crates from a stack of 0"
+        );
+    }
 }
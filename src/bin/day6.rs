@@ -1,6 +1,10 @@
 // https://adventofcode.com/2022/day/6
 
-use std::fs::read_to_string;
+use std::{
+    collections::VecDeque,
+    fs::{read_to_string, File},
+    io::{self, Read},
+};
 
 use itertools::Itertools;
 
@@ -35,6 +39,113 @@ fn solve_masks(input: &[char], marker_len: usize) -> usize {
     panic!("Failed to find marker");
 }
 
+/// A run of len distinct bytes, ending just before index end (i.e. end is the value `solve` returns)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Marker {
+    len: usize,
+    end: usize,
+}
+
+/// Sliding window over the last len bytes, counting each of the 256 byte values so any alphabet works
+struct Window {
+    len: usize,
+    counts: [usize; 256],
+    distinct: usize,
+}
+
+/// Finds every marker of several lengths in one pass over a stream, keeping only the last few bytes
+struct MarkerDetector {
+    windows: Vec<Window>,
+    // the last max marker length bytes, plus the newest one
+    history: VecDeque<u8>,
+    max_len: usize,
+    position: usize,
+}
+
+impl MarkerDetector {
+    fn new(lengths: &[usize]) -> MarkerDetector {
+        assert!(
+            lengths.iter().all(|&len| len > 0),
+            "Marker lengths must be positive"
+        );
+        let max_len = lengths.iter().copied().max().unwrap_or(0);
+
+        MarkerDetector {
+            windows: lengths
+                .iter()
+                .map(|&len| Window {
+                    len,
+                    counts: [0; 256],
+                    distinct: 0,
+                })
+                .collect(),
+            history: VecDeque::with_capacity(max_len + 1),
+            max_len,
+            position: 0,
+        }
+    }
+
+    /// Add the next byte, calling on_marker for every marker that ends with it
+    fn push(&mut self, byte: u8, mut on_marker: impl FnMut(Marker)) {
+        self.history.push_back(byte);
+        self.position += 1;
+
+        for window in &mut self.windows {
+            window.counts[byte as usize] += 1;
+            if window.counts[byte as usize] == 1 {
+                window.distinct += 1;
+            }
+
+            // drop the byte that just left the window
+            if self.history.len() > window.len {
+                let old = self.history[self.history.len() - 1 - window.len] as usize;
+                window.counts[old] -= 1;
+                if window.counts[old] == 0 {
+                    window.distinct -= 1;
+                }
+            }
+
+            if window.distinct == window.len {
+                on_marker(Marker {
+                    len: window.len,
+                    end: self.position,
+                });
+            }
+        }
+
+        if self.history.len() > self.max_len {
+            self.history.pop_front();
+        }
+    }
+
+    /// Feed everything from reader through the detector, a buffer at a time
+    fn scan<R: Read>(
+        &mut self,
+        mut reader: R,
+        mut on_marker: impl FnMut(Marker),
+    ) -> io::Result<()> {
+        let mut buffer = [0u8; 64 * 1024];
+        loop {
+            let num_read = match reader.read(&mut buffer) {
+                Ok(0) => return Ok(()),
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            for &byte in &buffer[..num_read] {
+                self.push(byte, &mut on_marker);
+            }
+        }
+    }
+}
+
+/// Every marker of each of the lengths, in the order they end
+fn find_markers<R: Read>(reader: R, lengths: &[usize]) -> io::Result<Vec<Marker>> {
+    let mut markers = vec![];
+    MarkerDetector::new(lengths).scan(reader, |marker| markers.push(marker))?;
+    Ok(markers)
+}
+
 fn main() {
     let input = read_to_string("input/day6-input.txt")
         .unwrap()
@@ -47,4 +158,77 @@ fn main() {
 
     assert_eq!(solve(&input, 4), solve_masks(&input, 4));
     assert_eq!(solve(&input, 14), solve_masks(&input, 14));
+
+    // the streaming detector reads the raw file, whose trailing newline can only add a marker at the very end
+    let markers = find_markers(File::open("input/day6-input.txt").unwrap(), &[4, 14]).unwrap();
+    for len in [4, 14] {
+        let mut ends = markers.iter().filter(|m| m.len == len).map(|m| m.end);
+        assert_eq!(ends.next(), Some(solve(&input, len)));
+        println!("{} markers of length {len}", ends.count() + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hands out at most 3 bytes per read, so markers straddle buffer boundaries
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.0.len().min(buf.len()).min(3);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    fn brute_force(input: &[u8], len: usize) -> Vec<usize> {
+        (len..=input.len())
+            .filter(|&end| input[end - len..end].iter().all_unique())
+            .collect()
+    }
+
+    #[test]
+    fn test_first_markers() {
+        let input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        let markers = find_markers(input.as_bytes(), &[4, 14]).unwrap();
+
+        let first = |len| markers.iter().find(|m| m.len == len).map(|m| m.end);
+        assert_eq!(first(4), Some(7));
+        assert_eq!(first(14), Some(19));
+
+        let chars = input.chars().collect_vec();
+        assert_eq!(first(4), Some(solve(&chars, 4)));
+        assert_eq!(first(14), Some(solve_masks(&chars, 14)));
+    }
+
+    #[test]
+    fn test_all_markers_any_bytes() {
+        // a pseudo-random stream over all byte values, with plenty of repeats
+        let input = (0u32..5000)
+            .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8 % if i % 7 == 0 { 255 } else { 17 })
+            .collect_vec();
+        let lengths = [4, 14, 30];
+
+        let markers = find_markers(Trickle(&input), &lengths).unwrap();
+        assert!(markers.windows(2).all(|w| w[0].end <= w[1].end));
+        for len in lengths {
+            let ends = markers
+                .iter()
+                .filter(|m| m.len == len)
+                .map(|m| m.end)
+                .collect_vec();
+            assert_eq!(ends, brute_force(&input, len), "len {len}");
+        }
+
+        // bytes outside a-z, including zero and 255
+        let input = [0u8, 255, 0, b'\n', 200, 255, 1];
+        let markers = find_markers(&input[..], &[3]).unwrap();
+        assert_eq!(
+            markers.iter().map(|m| m.end).collect_vec(),
+            brute_force(&input, 3)
+        );
+    }
 }
@@ -1,64 +1,55 @@
 // https://adventofcode.com/2022/day/9
 
-use std::{collections::HashSet, fs::read_to_string};
+use std::fs::read_to_string;
+
+use common::{BitGrid, CoordSet, Direction, Point2};
 
 #[derive(Debug)]
-struct Instruction {
+pub(crate) struct Instruction {
     count: usize, // number of times to repeat
-    dr: isize,    // row delta
-    dc: isize,    // column delta
-}
-
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-struct Coord {
-    row: isize,
-    col: isize,
+    direction: Direction,
 }
 
-fn parse_input(input: &String) -> Vec<Instruction> {
+pub(crate) fn parse_input(input: &String) -> Vec<Instruction> {
     input
         .lines()
         .map(|line| {
             let (dir, n) = line.split_once(" ").unwrap();
             let count = n.parse::<usize>().unwrap();
-            let (dr, dc) = match dir {
-                "U" => (-1, 0),
-                "D" => (1, 0),
-                "L" => (0, -1),
-                "R" => (0, 1),
-                _ => panic!("Unrecognized direction: {:?}", dir),
-            };
-            Instruction { count, dr, dc }
+            let direction = dir.parse().unwrap();
+            Instruction { count, direction }
         })
         .collect()
 }
 
 fn solve(instructions: &Vec<Instruction>, num_knots: usize) -> usize {
+    count_visited::<BitGrid>(instructions, num_knots)
+}
+
+/// The number of positions the tail visits, recording them in an S
+pub(crate) fn count_visited<S: CoordSet>(instructions: &[Instruction], num_knots: usize) -> usize {
     assert!(num_knots >= 2);
-    let mut rope = vec![Coord { row: 0, col: 0 }; num_knots];
-    let mut visited: HashSet<Coord> = HashSet::new();
+    let mut rope = vec![Point2::<isize>::default(); num_knots];
+    let mut visited = S::default();
 
     for instruction in instructions.iter() {
         for _ in 0..instruction.count {
-            rope[0].row += instruction.dr;
-            rope[0].col += instruction.dc;
+            rope[0] += instruction.direction.delta();
 
             for knot in 1..rope.len() {
-                if rope[knot - 1].row.abs_diff(rope[knot].row) > 1
-                    || rope[knot - 1].col.abs_diff(rope[knot].col) > 1
-                {
-                    rope[knot].row += (rope[knot - 1].row - rope[knot].row).signum();
-                    rope[knot].col += (rope[knot - 1].col - rope[knot].col).signum();
+                if rope[knot - 1].chebyshev_distance(rope[knot]) > 1 {
+                    let step = (rope[knot - 1] - rope[knot]).signum();
+                    rope[knot] += step;
                 } else {
                     break;
                 }
             }
 
-            visited.insert(*rope.last().unwrap());
+            visited.insert(rope.last().unwrap().to_row_col());
         }
     }
 
-    visited.len()
+    visited.count()
 }
 
 #[cfg(test)]
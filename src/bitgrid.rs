@@ -0,0 +1,250 @@
+//! A set of signed coordinates stored as a dense bitmap, which grows as needed.
+
+use std::collections::HashSet;
+
+use crate::SignedCoord;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A set of (row, col) coordinates stored as one bit per cell of a dense rectangle.  Coordinates may be negative,
+/// and the rectangle grows automatically when a coordinate outside it is inserted.
+///
+/// This is much faster than a `HashSet<SignedCoord>` for sets that are fairly densely packed into a region, which
+/// is what most puzzle simulations produce.
+#[derive(Clone, Debug, Default)]
+pub struct BitGrid {
+    // coordinate of the first bit, i.e. the top-left corner of the allocated rectangle
+    origin: SignedCoord,
+    // (rows, cols) of the allocated rectangle
+    shape: (usize, usize),
+    // bits of the rectangle in row-major order
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new() -> BitGrid {
+        BitGrid::default()
+    }
+
+    /// Create a grid with space allocated for the inclusive rectangle from min to max
+    pub fn with_bounds(min: SignedCoord, max: SignedCoord) -> BitGrid {
+        assert!(min.0 <= max.0 && min.1 <= max.1);
+        let shape = (min.0.abs_diff(max.0) + 1, min.1.abs_diff(max.1) + 1);
+
+        BitGrid {
+            origin: min,
+            shape,
+            words: vec![0; (shape.0 * shape.1).div_ceil(WORD_BITS)],
+        }
+    }
+
+    /// Linear bit index of coord, or None if it's outside the allocated rectangle
+    fn bit_index(&self, coord: SignedCoord) -> Option<usize> {
+        let row = usize::try_from(coord.0 - self.origin.0).ok()?;
+        let col = usize::try_from(coord.1 - self.origin.1).ok()?;
+
+        if row < self.shape.0 && col < self.shape.1 {
+            Some(row * self.shape.1 + col)
+        } else {
+            None
+        }
+    }
+
+    pub fn contains(&self, coord: SignedCoord) -> bool {
+        self.bit_index(coord)
+            .is_some_and(|i| self.words[i / WORD_BITS] & (1 << (i % WORD_BITS)) != 0)
+    }
+
+    /// Adds coord to the set.  Returns whether it was newly added.
+    pub fn insert(&mut self, coord: SignedCoord) -> bool {
+        let i = match self.bit_index(coord) {
+            Some(i) => i,
+            None => {
+                self.grow_to_include(coord);
+                self.bit_index(coord).unwrap()
+            }
+        };
+
+        let word = &mut self.words[i / WORD_BITS];
+        let mask = 1 << (i % WORD_BITS);
+        let was_set = *word & mask != 0;
+        *word |= mask;
+        !was_set
+    }
+
+    /// Removes coord from the set.  Returns whether it was present.
+    pub fn remove(&mut self, coord: SignedCoord) -> bool {
+        match self.bit_index(coord) {
+            Some(i) => {
+                let word = &mut self.words[i / WORD_BITS];
+                let mask = 1 << (i % WORD_BITS);
+                let was_set = *word & mask != 0;
+                *word &= !mask;
+                was_set
+            }
+            None => false,
+        }
+    }
+
+    /// Number of coordinates in the set
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// All coordinates in the set, in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = SignedCoord> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(move |(word_num, &word)| {
+                let mut bits = word;
+                std::iter::from_fn(move || {
+                    if bits == 0 {
+                        return None;
+                    }
+                    let bit = bits.trailing_zeros() as usize;
+                    bits &= bits - 1;
+
+                    let i = word_num * WORD_BITS + bit;
+                    let (row, col) = (i / self.shape.1, i % self.shape.1);
+                    Some((self.origin.0 + row as isize, self.origin.1 + col as isize))
+                })
+            })
+    }
+
+    /// Reallocate so that coord is inside the allocated rectangle.  Each dimension that needs to grow at least
+    /// doubles, so that a series of inserts moving steadily in one direction takes amortized constant time.
+    fn grow_to_include(&mut self, coord: SignedCoord) {
+        const INITIAL_SLACK: isize = 16;
+
+        let (min, max) = if self.words.is_empty() {
+            (
+                (coord.0 - INITIAL_SLACK, coord.1 - INITIAL_SLACK),
+                (coord.0 + INITIAL_SLACK, coord.1 + INITIAL_SLACK),
+            )
+        } else {
+            let (rows, cols) = (self.shape.0 as isize, self.shape.1 as isize);
+            let old_min = self.origin;
+            let old_max = (old_min.0 + rows - 1, old_min.1 + cols - 1);
+
+            let grow = |old_min: isize, old_max: isize, pos: isize, size: isize| {
+                if pos < old_min {
+                    (pos - size, old_max)
+                } else if pos > old_max {
+                    (old_min, pos + size)
+                } else {
+                    (old_min, old_max)
+                }
+            };
+
+            let (min_row, max_row) = grow(old_min.0, old_max.0, coord.0, rows);
+            let (min_col, max_col) = grow(old_min.1, old_max.1, coord.1, cols);
+            ((min_row, min_col), (max_row, max_col))
+        };
+
+        let mut grown = BitGrid::with_bounds(min, max);
+        for coord in self.iter() {
+            grown.insert(coord);
+        }
+        *self = grown;
+    }
+}
+
+/// The operations a simulation needs from a set of coordinates, so it can be run with either a `BitGrid` or a
+/// `HashSet` and the two compared
+pub trait CoordSet: Default {
+    fn contains(&self, coord: SignedCoord) -> bool;
+
+    /// Adds coord to the set.  Returns whether it was newly added.
+    fn insert(&mut self, coord: SignedCoord) -> bool;
+
+    /// Number of coordinates in the set
+    fn count(&self) -> usize;
+}
+
+impl CoordSet for BitGrid {
+    fn contains(&self, coord: SignedCoord) -> bool {
+        BitGrid::contains(self, coord)
+    }
+
+    fn insert(&mut self, coord: SignedCoord) -> bool {
+        BitGrid::insert(self, coord)
+    }
+
+    fn count(&self) -> usize {
+        self.count_ones()
+    }
+}
+
+impl CoordSet for HashSet<SignedCoord> {
+    fn contains(&self, coord: SignedCoord) -> bool {
+        HashSet::contains(self, &coord)
+    }
+
+    fn insert(&mut self, coord: SignedCoord) -> bool {
+        HashSet::insert(self, coord)
+    }
+
+    fn count(&self) -> usize {
+        self.len()
+    }
+}
+
+impl FromIterator<SignedCoord> for BitGrid {
+    fn from_iter<I: IntoIterator<Item = SignedCoord>>(iter: I) -> Self {
+        let mut grid = BitGrid::new();
+        for coord in iter {
+            grid.insert(coord);
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut grid = BitGrid::new();
+        assert!(!grid.contains((0, 0)));
+
+        assert!(grid.insert((0, 0)));
+        assert!(!grid.insert((0, 0)));
+        assert!(grid.insert((-100, 250)));
+        assert!(grid.contains((0, 0)));
+        assert!(grid.contains((-100, 250)));
+        assert!(!grid.contains((-100, 249)));
+        assert_eq!(grid.count_ones(), 2);
+
+        assert!(grid.remove((0, 0)));
+        assert!(!grid.remove((0, 0)));
+        assert!(!grid.remove((1000, 1000)));
+        assert_eq!(grid.iter().collect::<Vec<_>>(), vec![(-100, 250)]);
+    }
+
+    #[test]
+    fn test_matches_hashset() {
+        // pseudo-random walk, wandering well outside the initial allocation in every direction
+        let mut pos: SignedCoord = (0, 0);
+        let mut state: u64 = 12345;
+        let mut grid = BitGrid::new();
+        let mut set = HashSet::new();
+
+        for _ in 0..20000 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            match state >> 62 {
+                0 => pos.0 += 1,
+                1 => pos.0 -= 1,
+                2 => pos.1 += 1,
+                _ => pos.1 -= 1,
+            }
+            assert_eq!(grid.insert(pos), set.insert(pos));
+        }
+
+        assert_eq!(grid.count_ones(), set.len());
+        assert_eq!(grid.iter().collect::<HashSet<_>>(), set);
+    }
+}
@@ -0,0 +1,191 @@
+//! Coordinate compression: map the distinct values along an axis to dense indices, so that grids and sweeps
+//! need one cell per distinct edge rather than one per integer.
+
+use std::ops::Range;
+
+use crate::{interval::Interval, Grid, Integer};
+
+/// The sorted distinct values of one axis, each identified by its index.  Consecutive values delimit
+/// segments: segment i is values[i]..values[i + 1], so there is one fewer segment than values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedAxis<T> {
+    values: Vec<T>,
+}
+
+impl<T: Integer> CompressedAxis<T> {
+    pub fn new(values: impl IntoIterator<Item = T>) -> CompressedAxis<T> {
+        let mut values = values.into_iter().collect::<Vec<_>>();
+        values.sort_unstable();
+        values.dedup();
+        CompressedAxis { values }
+    }
+
+    /// Compress the edges of the intervals.  Each interval start..=end contributes start and end + 1, so every
+    /// segment is either entirely inside or entirely outside each of the intervals.
+    pub fn from_intervals(intervals: impl IntoIterator<Item = Interval<T>>) -> CompressedAxis<T> {
+        CompressedAxis::new(
+            intervals
+                .into_iter()
+                .flat_map(|interval| [interval.start(), interval.end() + T::ONE]),
+        )
+    }
+
+    /// The number of distinct values
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// The index of value, or None if it isn't one of the compressed values
+    pub fn index_of(&self, value: T) -> Option<usize> {
+        self.values.binary_search(&value).ok()
+    }
+
+    pub fn value_at(&self, index: usize) -> T {
+        self.values[index]
+    }
+
+    pub fn num_segments(&self) -> usize {
+        self.values.len().saturating_sub(1)
+    }
+
+    /// The index of the segment containing value, or None if it's outside all of them
+    pub fn segment_of(&self, value: T) -> Option<usize> {
+        let index = match self.values.binary_search(&value) {
+            Ok(index) => index,
+            Err(0) => return None,
+            Err(insert_at) => insert_at - 1,
+        };
+        (index < self.num_segments()).then_some(index)
+    }
+
+    /// The integers in segment index
+    pub fn segment(&self, index: usize) -> Interval<T> {
+        Interval::new(self.values[index], self.values[index + 1] - T::ONE)
+    }
+
+    /// The number of integers in segment index
+    pub fn segment_len(&self, index: usize) -> T {
+        self.values[index + 1] - self.values[index]
+    }
+
+    /// The indices of the segments making up interval, or None if its edges weren't compressed, e.g. because
+    /// it wasn't one of the intervals passed to `from_intervals`
+    pub fn segments_of(&self, interval: Interval<T>) -> Option<Range<usize>> {
+        let start = self.index_of(interval.start())?;
+        let end = self.index_of(interval.end() + T::ONE)?;
+        Some(start..end)
+    }
+}
+
+/// A compressed grid of the cells covered by the given (rows, cols) rectangles: cell (r, c) is true if the
+/// block of row segment r and column segment c is covered.  Returns the grid with the compressed row and
+/// column axes, or None if there are no rectangles.
+pub fn coverage_grid<T: Integer>(
+    rects: &[(Interval<T>, Interval<T>)],
+) -> Option<(Grid<bool>, CompressedAxis<T>, CompressedAxis<T>)> {
+    if rects.is_empty() {
+        return None;
+    }
+
+    let rows = CompressedAxis::from_intervals(rects.iter().map(|&(rows, _)| rows));
+    let cols = CompressedAxis::from_intervals(rects.iter().map(|&(_, cols)| cols));
+
+    // mark each rectangle's corners in a 2D difference array, so that its prefix sums count the rectangles
+    // covering each cell
+    let mut counts = vec![vec![0isize; cols.len()]; rows.len()];
+    for &(row_interval, col_interval) in rects {
+        let row_segments = rows.segments_of(row_interval).unwrap();
+        let col_segments = cols.segments_of(col_interval).unwrap();
+        counts[row_segments.start][col_segments.start] += 1;
+        counts[row_segments.start][col_segments.end] -= 1;
+        counts[row_segments.end][col_segments.start] -= 1;
+        counts[row_segments.end][col_segments.end] += 1;
+    }
+
+    for r in 0..rows.len() {
+        for c in 0..cols.len() {
+            let above = if r > 0 { counts[r - 1][c] } else { 0 };
+            let left = if c > 0 { counts[r][c - 1] } else { 0 };
+            let diagonal = if r > 0 && c > 0 {
+                counts[r - 1][c - 1]
+            } else {
+                0
+            };
+            counts[r][c] += above + left - diagonal;
+        }
+    }
+
+    let grid = Grid::new(
+        counts[..rows.num_segments()]
+            .iter()
+            .map(|row| row[..cols.num_segments()].iter().map(|&n| n > 0).collect())
+            .collect(),
+    );
+
+    Some((grid, rows, cols))
+}
+
+/// The number of integer cells covered by at least one of the (rows, cols) rectangles.  Takes time
+/// proportional to the square of the number of rectangles, however large they are.
+pub fn covered_area<T: Integer>(rects: &[(Interval<T>, Interval<T>)]) -> T {
+    let Some((grid, rows, cols)) = coverage_grid(rects) else {
+        return T::ZERO;
+    };
+
+    grid.all_coords()
+        .filter(|&coord| grid[coord])
+        .fold(T::ZERO, |area, (r, c)| {
+            area + rows.segment_len(r) * cols.segment_len(c)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_axis() {
+        let axis = CompressedAxis::from_intervals([
+            Interval::new(-2, 10),
+            Interval::new(4_000_000, 4_000_000),
+            Interval::new(5, 12),
+        ]);
+
+        assert_eq!(axis.values(), &[-2, 5, 11, 13, 4_000_000, 4_000_001]);
+        assert_eq!(axis.index_of(11), Some(2));
+        assert_eq!(axis.index_of(12), None);
+        assert_eq!(axis.segment_of(12), Some(2));
+        assert_eq!(axis.segment_of(-3), None);
+        assert_eq!(axis.segment_of(4_000_001), None);
+        assert_eq!(axis.segment(3), Interval::new(13, 3_999_999));
+        assert_eq!(axis.segment_len(3), 3_999_987);
+        assert_eq!(axis.segments_of(Interval::new(5, 12)), Some(1..3));
+        assert_eq!(axis.segments_of(Interval::new(5, 11)), None);
+    }
+
+    #[test]
+    fn test_covered_area() {
+        let rects = [
+            (Interval::new(0, 9), Interval::new(0, 9)),
+            (Interval::new(5, 14), Interval::new(5, 14)),
+            (Interval::new(100, 100), Interval::new(-1_000_000, 999_999)),
+        ];
+        assert_eq!(covered_area(&rects), 100 + 100 - 25 + 2_000_000);
+        assert_eq!(covered_area::<i64>(&[]), 0);
+
+        let (grid, rows, cols) = coverage_grid(&rects[..2]).unwrap();
+        assert_eq!(grid.shape(), (3, 3));
+        assert_eq!(rows.values(), &[0, 5, 10, 15]);
+        assert!(grid[(1, 1)]);
+        assert!(!grid[(0, 2)]);
+        assert_eq!(cols.segment(2), Interval::new(10, 14));
+    }
+}
@@ -0,0 +1,224 @@
+//! Cycle detection for deterministic simulations, to extrapolate them to step counts far too large to simulate.
+//!
+//! A simulation is described by an initial state and a step function.  Since the state may carry data that
+//! doesn't affect future steps (counters, histories), states are compared through a key function: two states
+//! with equal keys must evolve identically.
+
+use std::ops::Add;
+
+/// The shape of an eventually periodic sequence of states: the states from step `start` on repeat every
+/// `period` steps, and none of the states before `start` recur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The smallest step whose state is the same as the state at step n
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+}
+
+/// Find the cycle in the sequence of states starting at initial, using Brent's algorithm.  Takes O(start +
+/// period) steps and only keeps a couple of states in memory.
+///
+/// The sequence must eventually repeat, or this never returns.  That's guaranteed if there are finitely many
+/// distinct keys.
+pub fn find_cycle<S, K, F, G>(initial: &S, mut step: F, mut key: G) -> Cycle
+where
+    S: Clone,
+    K: Eq,
+    F: FnMut(&S) -> S,
+    G: FnMut(&S) -> K,
+{
+    // find the period: the hare runs ahead while the tortoise teleports to it at each power of two
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise_key = key(initial);
+    let mut hare = step(initial);
+
+    loop {
+        let hare_key = key(&hare);
+        if hare_key == tortoise_key {
+            break;
+        }
+        if power == period {
+            tortoise_key = hare_key;
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // find the start: with the hare a period ahead, they first meet at the start of the cycle
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, period }
+}
+
+/// The state after n steps, simulating at most start + period steps past the cycle search
+pub fn state_at<S, K, F, G>(initial: &S, mut step: F, key: G, n: usize) -> S
+where
+    S: Clone,
+    K: Eq,
+    F: FnMut(&S) -> S,
+    G: FnMut(&S) -> K,
+{
+    let cycle = find_cycle(initial, &mut step, key);
+
+    let mut state = initial.clone();
+    for _ in 0..cycle.equivalent_step(n) {
+        state = step(&state);
+    }
+    state
+}
+
+/// `count` copies of value added together, in O(log count) additions
+fn repeat_add<M: Clone + Default + Add<Output = M>>(value: M, mut count: usize) -> M {
+    let mut total = M::default();
+    let mut doubled = value;
+
+    while count > 0 {
+        if count & 1 == 1 {
+            total = total + doubled.clone();
+        }
+        count >>= 1;
+        if count > 0 {
+            doubled = doubled.clone() + doubled;
+        }
+    }
+
+    total
+}
+
+/// The sum of metric over the first n states, i.e. the metric accumulated over n steps if `metric(state)` is
+/// what a step from state contributes.  `M::default()` must be the zero of the addition.
+pub fn sum_at<S, K, M, F, G, H>(initial: &S, mut step: F, key: G, mut metric: H, n: usize) -> M
+where
+    S: Clone,
+    K: Eq,
+    M: Clone + Default + Add<Output = M>,
+    F: FnMut(&S) -> S,
+    G: FnMut(&S) -> K,
+    H: FnMut(&S) -> M,
+{
+    let cycle = find_cycle(initial, &mut step, key);
+
+    // n = start + full_cycles * period + remainder, and the sum splits up the same way
+    let (full_cycles, remainder) = if n < cycle.start {
+        (0, 0)
+    } else {
+        (
+            (n - cycle.start) / cycle.period,
+            (n - cycle.start) % cycle.period,
+        )
+    };
+
+    let mut before_cycle = M::default();
+    let mut one_cycle = M::default();
+    let mut partial_cycle = M::default();
+
+    let mut state = initial.clone();
+    for i in 0..n.min(cycle.start + cycle.period) {
+        let value = metric(&state);
+        if i < cycle.start {
+            before_cycle = before_cycle + value;
+        } else {
+            if i - cycle.start < remainder {
+                partial_cycle = partial_cycle + value.clone();
+            }
+            one_cycle = one_cycle + value;
+        }
+        state = step(&state);
+    }
+
+    if full_cycles == 0 {
+        // everything was simulated directly, and one_cycle holds the partial cycle
+        return before_cycle + one_cycle;
+    }
+
+    before_cycle + repeat_add(one_cycle, full_cycles) + partial_cycle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // x -> x^2 + 1 mod 255 from 3 runs 3, 10, 101, 2, 5, 26, 167, 95, 101, ...
+    fn step(x: &u32) -> u32 {
+        (x * x + 1) % 255
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        assert_eq!(
+            find_cycle(&3, step, |&x| x),
+            Cycle {
+                start: 2,
+                period: 6
+            }
+        );
+
+        // the key decides what counts as the same state; here the step counter is ignored
+        let counted = |&(x, n): &(u32, u64)| (step(&x), n + 1);
+        assert_eq!(
+            find_cycle(&(3, 0), counted, |&(x, _)| x),
+            Cycle {
+                start: 2,
+                period: 6
+            }
+        );
+
+        assert_eq!(
+            find_cycle(&7, |_| 7, |&x| x),
+            Cycle {
+                start: 0,
+                period: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_extrapolation() {
+        let states = [3, 10, 101, 2, 5, 26, 167, 95];
+        let n = 1_000_000_000_000usize;
+
+        assert_eq!(state_at(&3, step, |&x| x, 1), 10);
+        assert_eq!(state_at(&3, step, |&x| x, n), states[2 + (n - 2) % 6]);
+
+        let direct_sum = |n: usize| {
+            (0..n)
+                .map(|i| states[if i < 2 { i } else { 2 + (i - 2) % 6 }] as u64)
+                .sum::<u64>()
+        };
+        for n in [0, 1, 2, 5, 8, 9, 100, 101] {
+            assert_eq!(
+                sum_at(&3, step, |&x| x, |&x| x as u64, n),
+                direct_sum(n),
+                "n={n}"
+            );
+        }
+
+        let cycle_sum: u64 = states[2..].iter().map(|&x| x as u64).sum();
+        let expected = ((n as u64 - 2) / 6) * cycle_sum + direct_sum(2 + (n - 2) % 6);
+        assert_eq!(sum_at(&3, step, |&x| x, |&x| x as u64, n), expected);
+    }
+}
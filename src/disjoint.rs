@@ -0,0 +1,284 @@
+//! Union-find for incremental connectivity questions.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// A partition of the indices 0..len into disjoint sets, with path compression and union by rank so that
+/// every operation is effectively constant time.
+#[derive(Clone, Debug, Default)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    // only meaningful for roots
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl DisjointSet {
+    /// len singleton sets
+    pub fn new(len: usize) -> DisjointSet {
+        DisjointSet {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+            size: vec![1; len],
+            count: len,
+        }
+    }
+
+    /// The number of elements
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Add a new singleton set and return its element
+    pub fn add(&mut self) -> usize {
+        let element = self.parent.len();
+        self.parent.push(element);
+        self.rank.push(0);
+        self.size.push(1);
+        self.count += 1;
+        element
+    }
+
+    /// The representative of the set containing element
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        // point everything on the path straight at the root
+        let mut current = element;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+
+        root
+    }
+
+    /// Merge the sets containing a and b.  Returns false if they were already the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        // hang the shallower tree under the deeper one
+        let (child, root) = if self.rank[a] < self.rank[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        if self.rank[child] == self.rank[root] {
+            self.rank[root] += 1;
+        }
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
+        self.count -= 1;
+
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The number of disjoint sets
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The size of the set containing element
+    pub fn size_of(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.size[root]
+    }
+
+    /// The sizes of all the sets, largest first
+    pub fn component_sizes(&self) -> Vec<usize> {
+        let mut sizes = (0..self.len())
+            .filter(|&i| self.parent[i] == i)
+            .map(|i| self.size[i])
+            .collect::<Vec<_>>();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    /// The elements of each set, in order of their smallest element
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut index_of_root = HashMap::new();
+        let mut components: Vec<Vec<usize>> = vec![];
+
+        for element in 0..self.len() {
+            let root = self.find(element);
+            let index = *index_of_root.entry(root).or_insert_with(|| {
+                components.push(vec![]);
+                components.len() - 1
+            });
+            components[index].push(element);
+        }
+
+        components
+    }
+}
+
+/// A `DisjointSet` over arbitrary keys, which are added the first time they're seen
+#[derive(Clone, Debug)]
+pub struct KeyedDisjointSet<K> {
+    indices: HashMap<K, usize>,
+    keys: Vec<K>,
+    sets: DisjointSet,
+}
+
+impl<K> Default for KeyedDisjointSet<K> {
+    fn default() -> Self {
+        KeyedDisjointSet {
+            indices: HashMap::new(),
+            keys: vec![],
+            sets: DisjointSet::default(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> KeyedDisjointSet<K> {
+    pub fn new() -> KeyedDisjointSet<K> {
+        KeyedDisjointSet::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    /// Add key as a singleton set if it isn't already present, and return its dense index
+    pub fn insert(&mut self, key: K) -> usize {
+        if let Some(&index) = self.indices.get(&key) {
+            return index;
+        }
+        let index = self.sets.add();
+        self.indices.insert(key.clone(), index);
+        self.keys.push(key);
+        index
+    }
+
+    /// The representative key of the set containing key, or None if it hasn't been added
+    pub fn find(&mut self, key: &K) -> Option<&K> {
+        let index = *self.indices.get(key)?;
+        let root = self.sets.find(index);
+        Some(&self.keys[root])
+    }
+
+    /// Merge the sets containing a and b, adding them if necessary.  Returns false if they were already the
+    /// same set.
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let (a, b) = (self.insert(a), self.insert(b));
+        self.sets.union(a, b)
+    }
+
+    /// Whether a and b are both present and in the same set
+    pub fn same(&mut self, a: &K, b: &K) -> bool {
+        match (self.indices.get(a), self.indices.get(b)) {
+            (Some(&a), Some(&b)) => self.sets.same(a, b),
+            _ => false,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.sets.count()
+    }
+
+    /// The size of the set containing key, or None if it hasn't been added
+    pub fn size_of(&mut self, key: &K) -> Option<usize> {
+        let index = *self.indices.get(key)?;
+        Some(self.sets.size_of(index))
+    }
+
+    pub fn component_sizes(&self) -> Vec<usize> {
+        self.sets.component_sizes()
+    }
+
+    /// The keys of each set, in insertion order of their first key
+    pub fn components(&mut self) -> Vec<Vec<K>> {
+        self.sets
+            .components()
+            .into_iter()
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|i| self.keys[i].clone())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dense() {
+        let mut sets = DisjointSet::new(8);
+        assert_eq!(sets.count(), 8);
+
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.union(5, 6));
+
+        assert_eq!(sets.count(), 4);
+        assert!(sets.same(0, 3));
+        assert!(!sets.same(0, 5));
+        assert_eq!(sets.size_of(2), 4);
+        assert_eq!(sets.component_sizes(), vec![4, 2, 1, 1]);
+        assert_eq!(
+            sets.components(),
+            vec![vec![0, 1, 2, 3], vec![4], vec![5, 6], vec![7]]
+        );
+
+        let new = sets.add();
+        sets.union(new, 7);
+        assert_eq!(sets.size_of(7), 2);
+        assert_eq!(sets.count(), 4);
+    }
+
+    #[test]
+    fn test_keyed() {
+        // rock cells touching orthogonally form structures
+        let rocks = [(0, 0), (0, 1), (1, 1), (5, 5), (5, 6), (9, 0)];
+        let mut sets = KeyedDisjointSet::new();
+        for &(r, c) in &rocks {
+            sets.insert((r, c));
+            for neighbor in [(r - 1, c), (r, c - 1)] {
+                if sets.contains(&neighbor) {
+                    sets.union((r, c), neighbor);
+                }
+            }
+        }
+
+        assert_eq!(sets.len(), 6);
+        assert_eq!(sets.count(), 3);
+        assert!(sets.same(&(0, 0), &(1, 1)));
+        assert!(!sets.same(&(0, 0), &(5, 5)));
+        assert!(!sets.same(&(0, 0), &(7, 7)));
+        assert_eq!(sets.size_of(&(5, 6)), Some(2));
+        assert_eq!(sets.size_of(&(7, 7)), None);
+        let root = *sets.find(&(1, 1)).unwrap();
+        assert_eq!(sets.find(&(0, 1)), Some(&root));
+        assert_eq!(sets.component_sizes(), vec![3, 2, 1]);
+        assert_eq!(sets.components()[1], vec![(5, 5), (5, 6)]);
+    }
+}
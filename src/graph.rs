@@ -0,0 +1,245 @@
+//! Adapter exposing a `Grid` as an implicit petgraph graph, so petgraph's algorithms can run on a grid without
+//! first copying it into a `Graph`.
+
+use std::{collections::HashSet, vec};
+
+use petgraph::{
+    visit::{
+        Data, GraphBase, IntoEdgeReferences, IntoEdges, IntoEdgesDirected, IntoNeighbors,
+        IntoNeighborsDirected, IntoNodeIdentifiers, NodeCompactIndexable, NodeCount, NodeIndexable,
+        Visitable,
+    },
+    Direction,
+};
+
+use crate::{Coord, Grid};
+
+/// A directed graph whose nodes are the cells of a grid.  There is an edge from each cell to each of its up,
+/// down, left and right neighbors for which `has_edge(from_value, to_value)` is true.  Edges have no weight.
+///
+/// The petgraph traits are implemented for `&GridGraph`, so pass a reference to petgraph algorithms, e.g.
+/// `dijkstra(&graph, start, None, |_| 1)`.
+pub struct GridGraph<'a, T, F> {
+    grid: &'a Grid<T>,
+    has_edge: F,
+}
+
+pub type GridEdge<'a> = (Coord, Coord, &'a ());
+
+impl<'a, T, F> GridGraph<'a, T, F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    pub fn new(grid: &'a Grid<T>, has_edge: F) -> GridGraph<'a, T, F> {
+        GridGraph { grid, has_edge }
+    }
+
+    pub fn grid(&self) -> &'a Grid<T> {
+        self.grid
+    }
+
+    pub fn has_edge(&self, from: Coord, to: Coord) -> bool {
+        (self.has_edge)(&self.grid[from], &self.grid[to])
+    }
+
+    /// Neighbors with an edge to or from coord, depending on dir
+    fn neighbors_in_dir(&self, coord: Coord, dir: Direction) -> vec::IntoIter<Coord> {
+        self.grid
+            .neighbor_coords(coord)
+            .filter(|&nc| match dir {
+                Direction::Outgoing => self.has_edge(coord, nc),
+                Direction::Incoming => self.has_edge(nc, coord),
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Edges leaving or entering coord, depending on dir.  Edges are always (source, target, weight).
+    fn edges_in_dir(&self, coord: Coord, dir: Direction) -> vec::IntoIter<GridEdge<'static>> {
+        self.neighbors_in_dir(coord, dir)
+            .map(|nc| match dir {
+                Direction::Outgoing => (coord, nc, &()),
+                Direction::Incoming => (nc, coord, &()),
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+impl<'a, T, F> GraphBase for GridGraph<'a, T, F> {
+    type EdgeId = (Coord, Coord);
+    type NodeId = Coord;
+}
+
+impl<'a, T, F> Data for GridGraph<'a, T, F> {
+    type NodeWeight = T;
+    type EdgeWeight = ();
+}
+
+impl<'a, T, F> IntoNeighbors for &GridGraph<'a, T, F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    type Neighbors = vec::IntoIter<Coord>;
+
+    fn neighbors(self, a: Coord) -> Self::Neighbors {
+        self.neighbors_in_dir(a, Direction::Outgoing)
+    }
+}
+
+impl<'a, T, F> IntoNeighborsDirected for &GridGraph<'a, T, F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    type NeighborsDirected = vec::IntoIter<Coord>;
+
+    fn neighbors_directed(self, n: Coord, d: Direction) -> Self::NeighborsDirected {
+        self.neighbors_in_dir(n, d)
+    }
+}
+
+impl<'a, T, F> IntoEdgeReferences for &GridGraph<'a, T, F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    type EdgeRef = GridEdge<'static>;
+    type EdgeReferences = vec::IntoIter<GridEdge<'static>>;
+
+    fn edge_references(self) -> Self::EdgeReferences {
+        self.grid
+            .all_coords()
+            .flat_map(|coord| self.edges_in_dir(coord, Direction::Outgoing))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+impl<'a, T, F> IntoEdges for &GridGraph<'a, T, F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    type Edges = vec::IntoIter<GridEdge<'static>>;
+
+    fn edges(self, a: Coord) -> Self::Edges {
+        self.edges_in_dir(a, Direction::Outgoing)
+    }
+}
+
+impl<'a, T, F> IntoEdgesDirected for &GridGraph<'a, T, F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    type EdgesDirected = vec::IntoIter<GridEdge<'static>>;
+
+    fn edges_directed(self, a: Coord, dir: Direction) -> Self::EdgesDirected {
+        self.edges_in_dir(a, dir)
+    }
+}
+
+impl<'a, T, F> IntoNodeIdentifiers for &GridGraph<'a, T, F> {
+    type NodeIdentifiers = vec::IntoIter<Coord>;
+
+    fn node_identifiers(self) -> Self::NodeIdentifiers {
+        self.grid.all_coords().collect::<Vec<_>>().into_iter()
+    }
+}
+
+impl<'a, T, F> Visitable for GridGraph<'a, T, F> {
+    type Map = HashSet<Coord>;
+
+    fn visit_map(&self) -> Self::Map {
+        HashSet::with_capacity(self.grid.num_elems())
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        map.clear();
+    }
+}
+
+/// Nodes are indexed by their row-major linear index in the grid
+impl<'a, T, F> NodeIndexable for GridGraph<'a, T, F> {
+    fn node_bound(&self) -> usize {
+        self.grid.num_elems()
+    }
+
+    fn to_index(&self, a: Coord) -> usize {
+        self.grid.linear_index(a)
+    }
+
+    fn from_index(&self, i: usize) -> Coord {
+        (i / self.grid.num_cols(), i % self.grid.num_cols())
+    }
+}
+
+impl<'a, T, F> NodeCount for GridGraph<'a, T, F> {
+    fn node_count(&self) -> usize {
+        self.grid.num_elems()
+    }
+}
+
+impl<'a, T, F> NodeCompactIndexable for GridGraph<'a, T, F> {}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::{
+        algo::{astar, connected_components, dijkstra},
+        visit::Reversed,
+    };
+
+    use super::*;
+
+    fn heights() -> Grid<u32> {
+        Grid::new(vec![vec![0, 1, 2], vec![5, 5, 3], vec![7, 5, 4]])
+    }
+
+    // can climb at most 1, or descend any amount
+    fn climbable(from: &u32, to: &u32) -> bool {
+        from + 1 >= *to
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        let grid = heights();
+        let graph = GridGraph::new(&grid, climbable);
+
+        let lengths = dijkstra(&graph, (0, 0), None, |_| 1);
+        assert_eq!(lengths[&(2, 2)], 4);
+        assert_eq!(lengths[&(1, 0)], 7);
+
+        let (len, path) = astar(
+            &graph,
+            (0, 0),
+            |c| c == (2, 1),
+            |_| 1,
+            |(r, c): Coord| r.abs_diff(2) + c.abs_diff(1),
+        )
+        .unwrap();
+        assert_eq!(len, 5);
+        assert_eq!(path, vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn test_reversed() {
+        let grid = heights();
+        let graph = GridGraph::new(&grid, climbable);
+
+        // (2, 0) can't be reached from (0, 0), but (0, 0) can be reached from it
+        let forward = dijkstra(&graph, (0, 0), None, |_| 1);
+        let backward = dijkstra(Reversed(&graph), (0, 0), None, |_| 1);
+        assert!(!forward.contains_key(&(2, 0)));
+        assert_eq!(backward[&(2, 0)], 2);
+        assert_eq!(backward.len(), grid.num_elems());
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let grid = Grid::new(vec![
+            vec!['a', 'a', 'b'],
+            vec!['b', 'a', 'b'],
+            vec!['b', 'b', 'a'],
+        ]);
+        let graph = GridGraph::new(&grid, |a, b| a == b);
+
+        assert_eq!(connected_components(&graph), 4);
+    }
+}
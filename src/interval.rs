@@ -0,0 +1,387 @@
+//! Inclusive integer intervals, and sets of them.
+
+use std::fmt::{self, Display};
+
+use crate::Integer;
+
+/// The inclusive interval start..=end.  Never empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+/// Allen's interval relations: the 13 mutually exclusive ways two intervals can be placed relative to each
+/// other.  Because these are intervals of integers, "meets" means adjacent, e.g. 1..=3 meets 4..=6.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Relation {
+    Before,
+    Meets,
+    Overlaps,
+    Starts,
+    During,
+    Finishes,
+    Equals,
+    FinishedBy,
+    Contains,
+    StartedBy,
+    OverlappedBy,
+    MetBy,
+    After,
+}
+
+impl Relation {
+    /// The relation with the intervals swapped, so `b.relation(a) == a.relation(b).converse()`
+    pub fn converse(self) -> Relation {
+        match self {
+            Relation::Before => Relation::After,
+            Relation::Meets => Relation::MetBy,
+            Relation::Overlaps => Relation::OverlappedBy,
+            Relation::Starts => Relation::StartedBy,
+            Relation::During => Relation::Contains,
+            Relation::Finishes => Relation::FinishedBy,
+            Relation::Equals => Relation::Equals,
+            Relation::FinishedBy => Relation::Finishes,
+            Relation::Contains => Relation::During,
+            Relation::StartedBy => Relation::Starts,
+            Relation::OverlappedBy => Relation::Overlaps,
+            Relation::MetBy => Relation::Meets,
+            Relation::After => Relation::Before,
+        }
+    }
+}
+
+impl<T: Integer> Interval<T> {
+    pub fn new(start: T, end: T) -> Interval<T> {
+        assert!(start <= end, "Invalid interval {start:?}..={end:?}");
+        Interval { start, end }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    /// Number of integers in the interval
+    pub fn len(&self) -> T {
+        self.end - self.start + T::ONE
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// Whether every element of other is also in self
+    pub fn contains_interval(&self, other: Interval<T>) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    /// Whether the intervals have any elements in common
+    pub fn overlaps(&self, other: Interval<T>) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: Interval<T>) -> Option<Interval<T>> {
+        if self.overlaps(other) {
+            Some(Interval::new(
+                self.start.max(other.start),
+                self.end.min(other.end),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Whether the intervals overlap or are adjacent, so that their union is a single interval
+    fn touches(&self, other: Interval<T>) -> bool {
+        !matches!(self.relation(other), Relation::Before | Relation::After)
+    }
+
+    /// How self is placed relative to other
+    pub fn relation(&self, other: Interval<T>) -> Relation {
+        let (s1, e1, s2, e2) = (self.start, self.end, other.start, other.end);
+
+        // differences are only taken when known to be positive, so they can't overflow unsigned types
+        if e1 < s2 {
+            if s2 - e1 == T::ONE {
+                Relation::Meets
+            } else {
+                Relation::Before
+            }
+        } else if e2 < s1 {
+            if s1 - e2 == T::ONE {
+                Relation::MetBy
+            } else {
+                Relation::After
+            }
+        } else if s1 == s2 && e1 == e2 {
+            Relation::Equals
+        } else if s1 == s2 {
+            if e1 < e2 {
+                Relation::Starts
+            } else {
+                Relation::StartedBy
+            }
+        } else if e1 == e2 {
+            if s1 > s2 {
+                Relation::Finishes
+            } else {
+                Relation::FinishedBy
+            }
+        } else if s1 > s2 && e1 < e2 {
+            Relation::During
+        } else if s1 < s2 && e1 > e2 {
+            Relation::Contains
+        } else if s1 < s2 {
+            Relation::Overlaps
+        } else {
+            Relation::OverlappedBy
+        }
+    }
+}
+
+impl<T: Integer> From<(T, T)> for Interval<T> {
+    fn from((start, end): (T, T)) -> Self {
+        Interval::new(start, end)
+    }
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..={}", self.start, self.end)
+    }
+}
+
+/// A set of integers, stored as the minimal sorted list of disjoint intervals covering them
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    // sorted, and no two intervals overlap or are adjacent
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet { intervals: vec![] }
+    }
+
+    /// The disjoint intervals making up the set, in increasing order
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.intervals.iter().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of integers in the set
+    pub fn covered_len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::ZERO, |total, interval| total + interval.len())
+    }
+
+    /// The smallest interval containing the whole set, or None if it's empty
+    pub fn bounds(&self) -> Option<Interval<T>> {
+        let first = self.intervals.first()?;
+        let last = self.intervals.last()?;
+        Some(Interval::new(first.start, last.end))
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        // index of the first interval ending at or after value
+        let i = self
+            .intervals
+            .partition_point(|interval| interval.end < value);
+        i < self.intervals.len() && self.intervals[i].contains(value)
+    }
+
+    /// Add every element of interval to the set
+    pub fn insert(&mut self, interval: Interval<T>) {
+        // intervals before first_touching end before interval starts, and those from last_touching on start after
+        // it ends, so everything in between merges with it
+        let first_touching = self
+            .intervals
+            .partition_point(|other| other.relation(interval) == Relation::Before);
+        let after_touching = self
+            .intervals
+            .partition_point(|other| other.relation(interval) != Relation::After);
+
+        let merged = self.intervals[first_touching..after_touching].iter().fold(
+            interval,
+            |merged, other| {
+                debug_assert!(merged.touches(*other));
+                Interval::new(merged.start.min(other.start), merged.end.max(other.end))
+            },
+        );
+
+        self.intervals
+            .splice(first_touching..after_touching, [merged]);
+    }
+
+    /// Remove every element of interval from the set
+    pub fn remove(&mut self, interval: Interval<T>) {
+        *self = self.difference(&IntervalSet::from_iter([interval]));
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = self.clone();
+        for interval in other.iter() {
+            result.insert(interval);
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            if let Some(common) = a.intersection(b) {
+                intervals.push(common);
+            }
+
+            // whichever ends first can't overlap anything further in the other set
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        // the pieces are disjoint, and can't be adjacent since they come from non-adjacent intervals
+        IntervalSet { intervals }
+    }
+
+    /// Elements of self that aren't in other
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        match self.bounds() {
+            Some(bounds) => self.intersection(&other.complement(bounds)),
+            None => IntervalSet::new(),
+        }
+    }
+
+    /// Elements of within that aren't in the set
+    pub fn complement(&self, within: Interval<T>) -> IntervalSet<T> {
+        let mut intervals = vec![];
+        // next value not yet known to be in the set or the complement, or None once past the end of within
+        let mut next = Some(within.start);
+
+        for interval in self.iter() {
+            let Some(start) = next else {
+                break;
+            };
+            if interval.end < start {
+                continue;
+            }
+            if interval.start > within.end {
+                break;
+            }
+
+            if interval.start > start {
+                intervals.push(Interval::new(start, interval.start - T::ONE));
+            }
+            next = if interval.end < within.end {
+                Some(interval.end + T::ONE)
+            } else {
+                None
+            };
+        }
+
+        if let Some(start) = next {
+            intervals.push(Interval::new(start, within.end));
+        }
+
+        IntervalSet { intervals }
+    }
+
+    /// The intervals missing between the first and last elements of the set
+    pub fn gaps(&self) -> IntervalSet<T> {
+        match self.bounds() {
+            Some(bounds) => self.complement(bounds),
+            None => IntervalSet::new(),
+        }
+    }
+}
+
+impl<T: Integer> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        intervals.iter().map(|&i| Interval::from(i)).collect()
+    }
+
+    #[test]
+    fn test_relations() {
+        let i = |s, e| Interval::new(s, e);
+        let cases = [
+            (i(1, 2), i(4, 6), Relation::Before),
+            (i(1, 3), i(4, 6), Relation::Meets),
+            (i(1, 4), i(4, 6), Relation::Overlaps),
+            (i(4, 5), i(4, 6), Relation::Starts),
+            (i(5, 5), i(4, 6), Relation::During),
+            (i(5, 6), i(4, 6), Relation::Finishes),
+            (i(4, 6), i(4, 6), Relation::Equals),
+        ];
+
+        for (a, b, relation) in cases {
+            assert_eq!(a.relation(b), relation, "{a} vs {b}");
+            assert_eq!(b.relation(a), relation.converse(), "{b} vs {a}");
+        }
+
+        let u = Interval::new(0usize, 3);
+        assert_eq!(u.relation(Interval::new(4, 9)), Relation::Meets);
+        assert_eq!(u.len(), 4);
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let s = set(&[(10, 12), (1, 3), (5, 6), (4, 4), (20, 25), (11, 21)]);
+        assert_eq!(s, set(&[(1, 6), (10, 25)]));
+        assert_eq!(s.covered_len(), 22);
+        assert!(s.contains(6));
+        assert!(!s.contains(7));
+        assert!(s.contains(25));
+        assert_eq!(s.gaps(), set(&[(7, 9)]));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (40, 40)]);
+
+        assert_eq!(a.union(&b), set(&[(0, 30), (40, 40)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25)]));
+        assert_eq!(a.difference(&b), set(&[(0, 4), (26, 30)]));
+        assert_eq!(b.difference(&a), set(&[(11, 19), (40, 40)]));
+
+        assert_eq!(
+            a.complement(Interval::new(-5, 35)),
+            set(&[(-5, -1), (11, 19), (31, 35)])
+        );
+        assert_eq!(a.complement(Interval::new(2, 8)), set(&[]));
+        assert_eq!(a.complement(Interval::new(12, 15)), set(&[(12, 15)]));
+
+        let mut c = a.clone();
+        c.remove(Interval::new(5, 22));
+        assert_eq!(c, set(&[(0, 4), (23, 30)]));
+    }
+}
@@ -5,18 +5,80 @@ use std::path::Path;
 
 use itertools::Itertools;
 
+mod bitgrid;
+pub mod compress;
+pub mod cycle;
+mod disjoint;
+mod graph;
+pub mod interval;
+pub mod math;
+mod num;
+mod point;
+mod regions;
+mod render;
+pub mod search;
+mod sparse;
+mod summed;
+mod transform;
+mod voxel;
+
+pub use bitgrid::{BitGrid, CoordSet};
+pub use disjoint::{DisjointSet, KeyedDisjointSet};
+pub use graph::{GridEdge, GridGraph};
+pub use num::Integer;
+pub use point::{Direction, Point2, Point3};
+pub use regions::Components;
+pub use render::{Color, GridRenderer, Highlight};
+pub use sparse::SparseGrid;
+pub use summed::SummedAreaTable;
+pub use transform::GridView;
+pub use voxel::{Grid3, Neighborhood3, VoxelSet};
+
 pub fn read_lines<P>(filename: P) -> io::Result<Vec<String>>
 where
     P: AsRef<Path>,
 {
     let file = File::open(filename)?;
-    return io::BufReader::new(file).lines().collect();
+    io::BufReader::new(file).lines().collect()
 }
 
 pub type Coord = (usize, usize);
 
+/// A (row, col) coordinate that may be negative, for grids with no fixed origin
+pub type SignedCoord = (isize, isize);
+
+/// Which surrounding cells count as neighbors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// up, down, left and right
+    Orthogonal,
+    /// orthogonal neighbors plus the four diagonals
+    All,
+}
+
+impl Neighborhood {
+    /// (row, col) offsets from a cell to each of its neighbors
+    pub fn offsets(&self) -> &'static [SignedCoord] {
+        const OFFSETS: [SignedCoord; 8] = [
+            (-1, 0),
+            (1, 0),
+            (0, -1),
+            (0, 1),
+            (-1, -1),
+            (-1, 1),
+            (1, -1),
+            (1, 1),
+        ];
+
+        match self {
+            Neighborhood::Orthogonal => &OFFSETS[..4],
+            Neighborhood::All => &OFFSETS,
+        }
+    }
+}
+
 /// A 2D grid convenience type.  Supports indexing by Coord or row id
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Grid<T>(Vec<Vec<T>>);
 
 impl<T> Grid<T> {
@@ -48,24 +110,30 @@ impl<T> Grid<T> {
 
     /// coordinates of up, down, left and right neighbors
     pub fn neighbor_coords(&self, coord: Coord) -> impl Iterator<Item = Coord> {
+        self.neighbor_coords_in(coord, Neighborhood::Orthogonal)
+    }
+
+    /// coordinates of the neighbors in the given neighborhood that are inside the grid
+    pub fn neighbor_coords_in(
+        &self,
+        coord: Coord,
+        neighborhood: Neighborhood,
+    ) -> impl Iterator<Item = Coord> {
         let (row, col) = (coord.0 as isize, coord.1 as isize);
         let row_range = 0..(self.num_rows() as isize);
         let col_range = 0..(self.num_cols() as isize);
 
-        [
-            (row - 1, col),
-            (row + 1, col),
-            (row, col - 1),
-            (row, col + 1),
-        ]
-        .into_iter()
-        .filter_map(move |(nr, nc)| {
-            if row_range.contains(&nr) && col_range.contains(&nc) {
-                Some((nr as usize, nc as usize))
-            } else {
-                None
-            }
-        })
+        neighborhood
+            .offsets()
+            .iter()
+            .map(move |&(dr, dc)| (row + dr, col + dc))
+            .filter_map(move |(nr, nc)| {
+                if row_range.contains(&nr) && col_range.contains(&nc) {
+                    Some((nr as usize, nc as usize))
+                } else {
+                    None
+                }
+            })
     }
 
     /// All coordinates in the grid, in row-major order
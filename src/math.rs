@@ -0,0 +1,210 @@
+//! Number theory helpers: gcd, lcm, modular arithmetic and the Chinese Remainder Theorem.
+//!
+//! gcd and lcm are generic over the primitive integer types.  The modular arithmetic functions work on `i64` and
+//! `u64`, using 128 bit intermediates so they can't overflow.
+
+use crate::Integer;
+
+/// Greatest common divisor, always non-negative.  gcd(0, 0) is 0.
+///
+/// Panics if the result doesn't fit in T, which can only happen for `gcd(T::MIN, 0)` and similar.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    checked_gcd(a, b).expect("gcd overflowed")
+}
+
+/// Greatest common divisor, or None if the result doesn't fit in T
+pub fn checked_gcd<T: Integer>(mut a: T, mut b: T) -> Option<T> {
+    while b != T::ZERO {
+        // the remainder only fails to fit for T::MIN % -1, which is really 0
+        (a, b) = (b, a.checked_rem(b).unwrap_or(T::ZERO));
+    }
+
+    if a < T::ZERO {
+        a.checked_neg()
+    } else {
+        Some(a)
+    }
+}
+
+/// Least common multiple, always non-negative.  lcm(0, n) is 0.
+///
+/// Panics if the result doesn't fit in T.
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("lcm overflowed")
+}
+
+/// Least common multiple, or None if the result doesn't fit in T
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+
+    let multiple = (a / checked_gcd(a, b)?).checked_mul(b)?;
+    if multiple < T::ZERO {
+        multiple.checked_neg()
+    } else {
+        Some(multiple)
+    }
+}
+
+/// gcd of all values.  The gcd of no values is 0.
+pub fn gcd_all<T: Integer, I: IntoIterator<Item = T>>(values: I) -> T {
+    values.into_iter().fold(T::ZERO, gcd)
+}
+
+/// gcd of all values, or None if the result doesn't fit in T
+pub fn checked_gcd_all<T: Integer, I: IntoIterator<Item = T>>(values: I) -> Option<T> {
+    values.into_iter().try_fold(T::ZERO, checked_gcd)
+}
+
+/// lcm of all values.  The lcm of no values is 1.
+pub fn lcm_all<T: Integer, I: IntoIterator<Item = T>>(values: I) -> T {
+    values.into_iter().fold(T::ONE, lcm)
+}
+
+/// lcm of all values, or None if the result doesn't fit in T
+pub fn checked_lcm_all<T: Integer, I: IntoIterator<Item = T>>(values: I) -> Option<T> {
+    values.into_iter().try_fold(T::ONE, checked_lcm)
+}
+
+fn extended_gcd_i128(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (1, 0);
+    let (mut old_t, mut t) = (0, 1);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+        (old_t, t) = (t, old_t - q * t);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_s, -old_t)
+    } else {
+        (old_r, old_s, old_t)
+    }
+}
+
+/// Extended Euclidean algorithm.  Returns (g, x, y) where g = gcd(a, b) and a*x + b*y = g.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (g, x, y) = extended_gcd_i128(a as i128, b as i128);
+    (g as i64, x as i64, y as i64)
+}
+
+/// The x in 0..m with a*x ≡ 1 (mod m), or None if a and m aren't coprime.  m must be positive.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    assert!(m > 0, "Modulus must be positive, got {m}");
+    let (g, x, _) = extended_gcd_i128(a as i128, m as i128);
+    if g == 1 {
+        Some(x.rem_euclid(m as i128) as i64)
+    } else {
+        None
+    }
+}
+
+/// base^exp mod modulus.  modulus must be positive.
+pub fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    assert!(modulus > 0, "Modulus must be positive");
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result = 1 % modulus;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+
+    result as u64
+}
+
+/// Chinese Remainder Theorem.  Given congruences x ≡ remainder (mod modulus) as (remainder, modulus) pairs,
+/// returns (x, m) such that the solutions are exactly the numbers ≡ x (mod m), with 0 <= x < m and m the lcm of
+/// the moduli.  The moduli needn't be coprime.
+///
+/// Returns None if the congruences are inconsistent, or m doesn't fit in an i64.  Moduli must be positive.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    // merge the congruences one at a time into x ≡ r (mod m)
+    let (mut r, mut m): (i128, i128) = (0, 1);
+
+    for &(remainder, modulus) in congruences {
+        assert!(modulus > 0, "Modulus must be positive, got {modulus}");
+        let (remainder, modulus) = (remainder as i128, modulus as i128);
+
+        // need r + m*k ≡ remainder (mod modulus), i.e. m*k ≡ remainder - r
+        let (g, m_inverse, _) = extended_gcd_i128(m, modulus);
+        let diff = remainder - r;
+        if diff % g != 0 {
+            return None;
+        }
+
+        let step = modulus / g;
+        let k = (diff / g % step * m_inverse).rem_euclid(step);
+        let new_m = m
+            .checked_mul(step)
+            .filter(|&new_m| new_m <= i64::MAX as i128)?;
+
+        r = (r + m * k).rem_euclid(new_m);
+        m = new_m;
+    }
+
+    Some((r as i64, m as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12i32, 18), 6);
+        assert_eq!(gcd(0u8, 0), 0);
+        assert_eq!(lcm(4usize, 6), 12);
+        assert_eq!(lcm(-4i64, 6), 12);
+        assert_eq!(lcm(0, 5), 0);
+
+        assert_eq!(gcd_all([24, 36, 60]), 12);
+        assert_eq!(lcm_all([23usize, 19, 13, 17]), 96577);
+        assert_eq!(lcm_all([4usize, 6, 8]), 24);
+
+        assert_eq!(checked_gcd(i32::MIN, 0), None);
+        assert_eq!(checked_gcd(i32::MIN, -1), Some(1));
+        assert_eq!(gcd(-1, i64::MIN), 1);
+        assert_eq!(gcd(i8::MIN, 6), 2);
+        assert_eq!(checked_lcm(i16::MIN, -1), None);
+        assert_eq!(checked_lcm(u8::MAX, 2), None);
+        assert_eq!(
+            checked_lcm_all([1u64 << 40, 3 << 30, 5 << 20]),
+            Some(15 << 40)
+        );
+        assert_eq!(checked_lcm_all([u64::MAX, u64::MAX - 1]), None);
+    }
+
+    #[test]
+    fn test_modular() {
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, u64::MAX - 1), 1);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(-1, 4), (3, 6)]), Some((3, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(1, i64::MAX), (0, i64::MAX - 1)]), None);
+    }
+}
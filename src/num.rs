@@ -0,0 +1,118 @@
+//! A small numeric trait so geometry and interval types can be generic over the primitive integer types.
+
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    ops::{Add, Div, Mul, Rem, Sub},
+};
+
+/// The operations on primitive integers that the generic types in this crate need
+pub trait Integer:
+    Copy
+    + Ord
+    + Hash
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// -1, 0 or 1 depending on the sign.  Unsigned types only ever return 0 or 1.
+    fn signum(self) -> Self;
+
+    /// |self - other|.  Never overflows for unsigned types, but for signed types panics if the difference is
+    /// larger than `Self::MAX`, e.g. for `i32::MIN` and `i32::MAX`.
+    fn abs_diff(self, other: Self) -> Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_rem(self, other: Self) -> Option<Self>;
+    fn checked_neg(self) -> Option<Self>;
+}
+
+// checked operations are the same for signed and unsigned types
+macro_rules! checked_ops {
+    ($t:ty) => {
+        fn checked_add(self, other: Self) -> Option<Self> {
+            <$t>::checked_add(self, other)
+        }
+
+        fn checked_mul(self, other: Self) -> Option<Self> {
+            <$t>::checked_mul(self, other)
+        }
+
+        fn checked_rem(self, other: Self) -> Option<Self> {
+            <$t>::checked_rem(self, other)
+        }
+
+        fn checked_neg(self) -> Option<Self> {
+            <$t>::checked_neg(self)
+        }
+    };
+}
+
+macro_rules! impl_signed {
+    ($($t:ty)*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn signum(self) -> Self {
+                <$t>::signum(self)
+            }
+
+            fn abs_diff(self, other: Self) -> Self {
+                <$t>::try_from(<$t>::abs_diff(self, other)).unwrap_or_else(|_| {
+                    panic!("|{self} - {other}| doesn't fit in {}", stringify!($t))
+                })
+            }
+
+            checked_ops!($t);
+        }
+    )*};
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty)*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn signum(self) -> Self {
+                (self > 0) as $t
+            }
+
+            fn abs_diff(self, other: Self) -> Self {
+                <$t>::abs_diff(self, other)
+            }
+
+            checked_ops!($t);
+        }
+    )*};
+}
+
+impl_signed!(i8 i16 i32 i64 i128 isize);
+impl_unsigned!(u8 u16 u32 u64 u128 usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abs_diff() {
+        assert_eq!(Integer::abs_diff(-3i32, 4), 7);
+        assert_eq!(Integer::abs_diff(i8::MAX, 0), i8::MAX);
+        assert_eq!(Integer::abs_diff(i8::MIN + 1, 0), i8::MAX);
+        assert_eq!(Integer::abs_diff(0u8, u8::MAX), u8::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "|-2147483648 - 2147483647| doesn't fit in i32")]
+    fn test_abs_diff_overflow() {
+        Integer::abs_diff(i32::MIN, i32::MAX);
+    }
+}
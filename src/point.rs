@@ -0,0 +1,332 @@
+//! 2D and 3D points, and compass directions.
+
+use std::{
+    fmt::{self, Display},
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use crate::Integer;
+
+/// A 2D point or vector.  x grows to the right and y grows downward, matching the row and column layout of
+/// puzzle inputs, so `Direction::Up` is negative y.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Integer> Point2<T> {
+    pub fn new(x: T, y: T) -> Point2<T> {
+        Point2 { x, y }
+    }
+
+    /// Convert from a (row, col) pair, as used by `Grid`, `SparseGrid` and `BitGrid`
+    pub fn from_row_col((row, col): (T, T)) -> Point2<T> {
+        Point2 { x: col, y: row }
+    }
+
+    /// Convert to a (row, col) pair, as used by `Grid`, `SparseGrid` and `BitGrid`
+    pub fn to_row_col(self) -> (T, T) {
+        (self.y, self.x)
+    }
+
+    /// Component-wise signum, i.e. a unit step toward this vector's direction, including diagonally
+    pub fn signum(self) -> Point2<T> {
+        Point2::new(self.x.signum(), self.y.signum())
+    }
+
+    /// Taxicab distance: the number of orthogonal steps between the points
+    pub fn manhattan_distance(self, other: Point2<T>) -> T {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Chessboard distance: the number of steps between the points when diagonal steps are allowed
+    pub fn chebyshev_distance(self, other: Point2<T>) -> T {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+}
+
+impl<T: Integer> Add for Point2<T> {
+    type Output = Point2<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Integer> Sub for Point2<T> {
+    type Output = Point2<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Integer> AddAssign for Point2<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Integer> SubAssign for Point2<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Integer> Mul<T> for Point2<T> {
+    type Output = Point2<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Point2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Integer + Neg<Output = T>> Neg for Point2<T> {
+    type Output = Point2<T>;
+
+    fn neg(self) -> Self::Output {
+        Point2::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Display> Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// A 3D point or vector
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Integer> Point3<T> {
+    pub fn new(x: T, y: T, z: T) -> Point3<T> {
+        Point3 { x, y, z }
+    }
+
+    /// Component-wise signum, i.e. a unit step toward this vector's direction, including diagonally
+    pub fn signum(self) -> Point3<T> {
+        Point3::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+
+    /// Component-wise minimum, e.g. the low corner of a bounding box
+    pub fn min(self, other: Point3<T>) -> Point3<T> {
+        Point3::new(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+    }
+
+    /// Component-wise maximum, e.g. the high corner of a bounding box
+    pub fn max(self, other: Point3<T>) -> Point3<T> {
+        Point3::new(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+    }
+
+    /// The number of face-to-face steps between the points
+    pub fn manhattan_distance(self, other: Point3<T>) -> T {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    /// The number of steps between the points when diagonal steps are allowed
+    pub fn chebyshev_distance(self, other: Point3<T>) -> T {
+        self.x
+            .abs_diff(other.x)
+            .max(self.y.abs_diff(other.y))
+            .max(self.z.abs_diff(other.z))
+    }
+}
+
+impl<T: Integer> Add for Point3<T> {
+    type Output = Point3<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Integer> Sub for Point3<T> {
+    type Output = Point3<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Integer> AddAssign for Point3<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Integer> SubAssign for Point3<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Integer> Mul<T> for Point3<T> {
+    type Output = Point3<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Point3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl<T: Integer + Neg<Output = T>> Neg for Point3<T> {
+    type Output = Point3<T>;
+
+    fn neg(self) -> Self::Output {
+        Point3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Display> Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    /// Unit vector pointing in this direction
+    pub fn delta<T: Integer + Neg<Output = T>>(self) -> Point2<T> {
+        match self {
+            Direction::Up => Point2::new(T::ZERO, -T::ONE),
+            Direction::Down => Point2::new(T::ZERO, T::ONE),
+            Direction::Left => Point2::new(-T::ONE, T::ZERO),
+            Direction::Right => Point2::new(T::ONE, T::ZERO),
+        }
+    }
+
+    /// Direction after turning 90 degrees counter-clockwise
+    pub fn turn_left(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    /// Direction after turning 90 degrees clockwise
+    pub fn turn_right(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.turn_left().turn_left()
+    }
+}
+
+/// Parses the U, D, L, R notation used by puzzle inputs
+impl TryFrom<char> for Direction {
+    type Error = String;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'U' => Ok(Direction::Up),
+            'D' => Ok(Direction::Down),
+            'L' => Ok(Direction::Left),
+            'R' => Ok(Direction::Right),
+            _ => Err(format!("Invalid direction: {value:?}")),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => Direction::try_from(ch),
+            _ => Err(format!("Invalid direction: {s:?}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic_and_distances() {
+        let p = Point2::new(3isize, -4);
+        let q = Point2::new(-1isize, 2);
+
+        assert_eq!(p + q, Point2::new(2, -2));
+        assert_eq!(p - q, Point2::new(4, -6));
+        assert_eq!(q * 3, Point2::new(-3, 6));
+        assert_eq!((p - q).signum(), Point2::new(1, -1));
+        assert_eq!(p.manhattan_distance(q), 10);
+        assert_eq!(p.chebyshev_distance(q), 6);
+
+        let u = Point2::new(2usize, 7);
+        assert_eq!(u.manhattan_distance(Point2::new(5, 1)), 9);
+        assert_eq!(u.to_row_col(), (7, 2));
+    }
+
+    #[test]
+    fn test_point3() {
+        let p = Point3::new(1i32, -2, 3);
+        let q = Point3::new(-1i32, 2, 0);
+
+        assert_eq!(p + q, Point3::new(0, 0, 3));
+        assert_eq!(p - q, Point3::new(2, -4, 3));
+        assert_eq!(-(q * 2), Point3::new(2, -4, 0));
+        assert_eq!((q - p).signum(), Point3::new(-1, 1, -1));
+        assert_eq!(p.min(q), Point3::new(-1, -2, 0));
+        assert_eq!(p.max(q), Point3::new(1, 2, 3));
+        assert_eq!(p.manhattan_distance(q), 9);
+        assert_eq!(p.chebyshev_distance(q), 4);
+        assert_eq!(p.to_string(), "(1, -2, 3)");
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!("U".parse::<Direction>(), Ok(Direction::Up));
+        assert_eq!(Direction::try_from('R'), Ok(Direction::Right));
+        assert!("X".parse::<Direction>().is_err());
+        assert!("UD".parse::<Direction>().is_err());
+
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(
+                dir.delta::<i32>() + dir.opposite().delta(),
+                Point2::default()
+            );
+        }
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.delta::<i32>(), Point2::new(0, -1));
+    }
+}
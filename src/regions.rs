@@ -0,0 +1,228 @@
+//! Flood fill and connected-component labelling on grids.
+
+use std::collections::{HashSet, VecDeque};
+
+use itertools::Itertools;
+
+use crate::{Coord, Grid, Neighborhood};
+
+impl<T> Grid<T> {
+    /// All cells reachable from seed, including seed itself, stepping from a cell to a neighbor whenever
+    /// `can_enter(from_value, to_value)` is true.  The predicate needn't be symmetric, so this can answer
+    /// directed questions like which cells can be climbed to from seed.
+    pub fn flood_fill<F>(
+        &self,
+        seed: Coord,
+        neighborhood: Neighborhood,
+        mut can_enter: F,
+    ) -> HashSet<Coord>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut filled = HashSet::from([seed]);
+        let mut to_visit = VecDeque::from([seed]);
+
+        while let Some(coord) = to_visit.pop_front() {
+            for nc in self.neighbor_coords_in(coord, neighborhood) {
+                if !filled.contains(&nc) && can_enter(&self[coord], &self[nc]) {
+                    filled.insert(nc);
+                    to_visit.push_back(nc);
+                }
+            }
+        }
+
+        filled
+    }
+
+    /// Partition the grid into connected components, where neighboring cells are in the same component if
+    /// `connected(value1, value2)` is true.  The predicate should be symmetric, e.g. `|a, b| a == b` to find
+    /// regions of equal cells.
+    pub fn label_components<F>(&self, neighborhood: Neighborhood, mut connected: F) -> Components
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        const UNLABELLED: usize = usize::MAX;
+
+        let mut labels = Grid::new(vec![vec![UNLABELLED; self.num_cols()]; self.num_rows()]);
+        let mut sizes = vec![];
+        let mut bounding_boxes = vec![];
+
+        for seed in self.all_coords() {
+            if labels[seed] != UNLABELLED {
+                continue;
+            }
+
+            let label = sizes.len();
+            let component = self.flood_fill(seed, neighborhood, &mut connected);
+
+            for &coord in component.iter() {
+                labels[coord] = label;
+            }
+
+            let (min_row, max_row) = component
+                .iter()
+                .map(|c| c.0)
+                .minmax()
+                .into_option()
+                .unwrap();
+            let (min_col, max_col) = component
+                .iter()
+                .map(|c| c.1)
+                .minmax()
+                .into_option()
+                .unwrap();
+
+            sizes.push(component.len());
+            bounding_boxes.push(((min_row, min_col), (max_row, max_col)));
+        }
+
+        Components {
+            labels,
+            sizes,
+            bounding_boxes,
+        }
+    }
+}
+
+/// The result of `Grid::label_components`.  Components are labelled 0, 1, 2, ... in the row-major order of
+/// their first cell.
+#[derive(Debug, Clone)]
+pub struct Components {
+    labels: Grid<usize>,
+    sizes: Vec<usize>,
+    bounding_boxes: Vec<(Coord, Coord)>,
+}
+
+impl Components {
+    /// A grid of the component label of every cell
+    pub fn labels(&self) -> &Grid<usize> {
+        &self.labels
+    }
+
+    pub fn label(&self, coord: Coord) -> usize {
+        self.labels[coord]
+    }
+
+    /// Number of components
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Number of cells in each component, indexed by label
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    /// Inclusive (min, max) corners of each component, indexed by label
+    pub fn bounding_boxes(&self) -> &[(Coord, Coord)] {
+        &self.bounding_boxes
+    }
+
+    /// Coordinates of the cells in a component, in row-major order
+    pub fn coords(&self, label: usize) -> impl Iterator<Item = Coord> + '_ {
+        let ((min_row, min_col), (max_row, max_col)) = self.bounding_boxes[label];
+        (min_row..=max_row)
+            .cartesian_product(min_col..=max_col)
+            .filter(move |&c| self.labels[c] == label)
+    }
+
+    /// Number of cell sides that a component shares with the outside of the component, including the grid edge
+    fn outside_sides(&self, coord: Coord) -> usize {
+        let label = self.labels[coord];
+        let inside = self
+            .labels
+            .neighbor_coords(coord)
+            .filter(|&nc| self.labels[nc] == label)
+            .count();
+        4 - inside
+    }
+
+    /// Cells of a component that are orthogonally adjacent to another component or to the grid edge, in
+    /// row-major order
+    pub fn boundary(&self, label: usize) -> Vec<Coord> {
+        self.coords(label)
+            .filter(|&c| self.outside_sides(c) > 0)
+            .collect()
+    }
+
+    /// Length of the outline of a component, counting each exposed cell side as 1, including the outlines of
+    /// any holes
+    pub fn perimeter(&self, label: usize) -> usize {
+        self.coords(label).map(|c| self.outside_sides(c)).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(rows: &[&str]) -> Grid<char> {
+        Grid::new(rows.iter().map(|r| r.chars().collect()).collect())
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let g = grid(&["..#..", ".##..", "#...#"]);
+        let open = |_: &char, to: &char| *to == '.';
+
+        assert_eq!(
+            g.flood_fill((0, 0), Neighborhood::Orthogonal, open).len(),
+            3
+        );
+        assert_eq!(
+            g.flood_fill((0, 4), Neighborhood::Orthogonal, open).len(),
+            7
+        );
+
+        // diagonal steps join the two open regions
+        assert_eq!(g.flood_fill((0, 0), Neighborhood::All, open).len(), 10);
+    }
+
+    #[test]
+    fn test_label_components() {
+        let g = grid(&["aab", "abb", "cca"]);
+        let components = g.label_components(Neighborhood::Orthogonal, |a, b| a == b);
+
+        assert_eq!(components.count(), 4);
+        assert_eq!(components.sizes(), &[3, 3, 2, 1]);
+        assert_eq!(components.label((1, 2)), 1);
+        assert_eq!(components.bounding_boxes()[1], ((0, 1), (1, 2)));
+        assert_eq!(
+            components.coords(2).collect::<Vec<_>>(),
+            vec![(2, 0), (2, 1)]
+        );
+
+        let checkerboard = grid(&["ab", "ba"]);
+        let same = |a: &char, b: &char| a == b;
+        assert_eq!(
+            checkerboard
+                .label_components(Neighborhood::Orthogonal, same)
+                .count(),
+            4
+        );
+        assert_eq!(
+            checkerboard
+                .label_components(Neighborhood::All, same)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_boundary_and_perimeter() {
+        let g = grid(&[".....", ".###.", ".#.#.", ".###.", "....."]);
+        let components = g.label_components(Neighborhood::Orthogonal, |a, b| a == b);
+
+        // outer ring of dots, ring of hashes, and the single dot in the middle
+        assert_eq!(components.count(), 3);
+        let ring = components.label((1, 1));
+
+        assert_eq!(components.sizes()[ring], 8);
+        assert_eq!(components.perimeter(ring), 16);
+        assert_eq!(components.boundary(ring).len(), 8);
+
+        let outside = components.label((0, 0));
+        assert_eq!(components.perimeter(outside), 20 + 12);
+        assert_eq!(components.boundary(outside).len(), 16);
+    }
+}
@@ -0,0 +1,222 @@
+//! Text rendering of grids, for printing puzzle state while debugging.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use crate::{Coord, Grid};
+
+/// ANSI terminal colours usable for highlighting cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Color {
+    fn ansi_code(&self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+        }
+    }
+}
+
+/// How an overlaid cell is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    /// Replace the cell's text with this character
+    Marker(char),
+    /// Draw the cell's text in this colour
+    Color(Color),
+}
+
+/// Renders a grid using a per-cell formatter, with optional highlighted overlays and index gutters.  Create one
+/// with `Grid::renderer`, and print it with `{}`.
+pub struct GridRenderer<'a, T, F> {
+    grid: &'a Grid<T>,
+    format_cell: F,
+    highlights: HashMap<Coord, Highlight>,
+    gutters: bool,
+}
+
+impl<'a, T, F> GridRenderer<'a, T, F>
+where
+    F: Fn(&T) -> String,
+{
+    /// Highlight the given coordinates.  Where overlays overlap, the last one added wins.
+    pub fn overlay<I>(mut self, coords: I, highlight: Highlight) -> Self
+    where
+        I: IntoIterator<Item = Coord>,
+    {
+        self.highlights
+            .extend(coords.into_iter().map(|coord| (coord, highlight)));
+        self
+    }
+
+    /// Label rows and columns with their indexes
+    pub fn with_gutters(mut self) -> Self {
+        self.gutters = true;
+        self
+    }
+}
+
+impl<'a, T, F> Display for GridRenderer<'a, T, F>
+where
+    F: Fn(&T) -> String,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells = self
+            .grid
+            .0
+            .iter()
+            .map(|row| row.iter().map(&self.format_cell).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        // every cell is padded to the same width so columns line up
+        let cell_width = cells.iter().flatten().map(|s| s.chars().count()).max();
+        let cell_width = cell_width.unwrap_or(1).max(1);
+        // single character cells read fine packed together, but wider ones need a gap
+        let separator = if cell_width > 1 { " " } else { "" };
+
+        let row_label_width = self.grid.num_rows().saturating_sub(1).to_string().len();
+
+        if self.gutters {
+            // column indexes are written vertically, one digit per line, so they fit over narrow cells
+            let col_labels = (0..self.grid.num_cols())
+                .map(|col| col.to_string())
+                .collect::<Vec<_>>();
+            let num_label_lines = col_labels.last().map_or(0, |label| label.len());
+
+            for line in 0..num_label_lines {
+                write!(f, "{:row_label_width$} ", "")?;
+                for (col, label) in col_labels.iter().enumerate() {
+                    if col > 0 {
+                        f.write_str(separator)?;
+                    }
+                    // right-align the digits, so the ones digit is always on the last line
+                    let pad = num_label_lines - label.len();
+                    let digit = if line < pad {
+                        ' '
+                    } else {
+                        label.as_bytes()[line - pad] as char
+                    };
+                    write!(f, "{digit:>cell_width$}")?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        for (row, row_cells) in cells.iter().enumerate() {
+            if self.gutters {
+                write!(f, "{row:>row_label_width$} ")?;
+            }
+
+            for (col, cell) in row_cells.iter().enumerate() {
+                if col > 0 {
+                    f.write_str(separator)?;
+                }
+                match self.highlights.get(&(row, col)) {
+                    None => write!(f, "{cell:>cell_width$}")?,
+                    Some(Highlight::Marker(marker)) => write!(f, "{marker:>cell_width$}")?,
+                    Some(Highlight::Color(color)) => {
+                        write!(f, "\x1b[{}m{cell:>cell_width$}\x1b[0m", color.ansi_code())?
+                    }
+                }
+            }
+
+            if row + 1 < cells.len() {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Grid<T> {
+    /// Start rendering this grid, formatting each cell with `format_cell`
+    pub fn renderer<F>(&self, format_cell: F) -> GridRenderer<'_, T, F>
+    where
+        F: Fn(&T) -> String,
+    {
+        GridRenderer {
+            grid: self,
+            format_cell,
+            highlights: HashMap::new(),
+            gutters: false,
+        }
+    }
+}
+
+/// Renders one line per row, with cells right-aligned to the widest cell and separated by a space if wider than
+/// one character
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.renderer(|cell| cell.to_string()).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(rows: &[&str]) -> Grid<char> {
+        Grid::new(rows.iter().map(|r| r.chars().collect()).collect())
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(grid(&["ab", "cd"]).to_string(), "ab\ncd");
+        assert_eq!(
+            Grid::new(vec![vec![1, 10], vec![100, 2]]).to_string(),
+            "  1  10\n100   2"
+        );
+        assert_eq!(
+            Grid::new(vec![vec![12, 34], vec![5, 6]]).to_string(),
+            "12 34\n 5  6"
+        );
+    }
+
+    #[test]
+    fn test_overlays() {
+        let g = grid(&["abc", "def"]);
+
+        let rendered = g
+            .renderer(|c| c.to_string())
+            .overlay([(0, 0), (1, 1)], Highlight::Marker('#'))
+            .overlay([(1, 1)], Highlight::Marker('@'))
+            .to_string();
+        assert_eq!(rendered, "#bc\nd@f");
+
+        let rendered = g
+            .renderer(|c| c.to_string())
+            .overlay([(0, 2)], Highlight::Color(Color::Red))
+            .to_string();
+        assert_eq!(rendered, "ab\x1b[31mc\x1b[0m\ndef");
+    }
+
+    #[test]
+    fn test_gutters() {
+        let g = Grid::new(vec![vec!['.'; 11]; 2]);
+        let rendered = g.renderer(|c| c.to_string()).with_gutters().to_string();
+        assert_eq!(
+            rendered,
+            "            1\n  01234567890\n0 ...........\n1 ..........."
+        );
+
+        // labels stay over their columns when cells are separated
+        let g = Grid::new(vec![vec![12, 34], vec![5, 6]]);
+        let rendered = g.renderer(|n| n.to_string()).with_gutters().to_string();
+        assert_eq!(rendered, "   0  1\n0 12 34\n1  5  6");
+    }
+}
@@ -0,0 +1,327 @@
+//! Shortest-path searches over implicit graphs.
+//!
+//! The graph is never built: callers supply a closure returning the neighbors of a node, so any `Hash + Eq` type
+//! can be a node, whether it's a grid coordinate or a whole puzzle state.  All searches accept several source
+//! nodes, and an optional goal predicate that stops the search as soon as a matching node is reached.
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// The outcome of a search: the cost of reaching each node and how it was reached
+#[derive(Debug, Clone)]
+pub struct SearchResult<N, C> {
+    /// Cost of the cheapest path to every node the search settled.  Sources have cost zero.
+    pub costs: HashMap<N, C>,
+    /// The node each settled node was reached from.  Sources have no entry.
+    pub predecessors: HashMap<N, N>,
+    /// The first node matching the goal predicate, if there was one and it was reached
+    pub goal: Option<N>,
+}
+
+impl<N: Hash + Eq + Clone, C> SearchResult<N, C> {
+    fn new() -> SearchResult<N, C> {
+        SearchResult {
+            costs: HashMap::new(),
+            predecessors: HashMap::new(),
+            goal: None,
+        }
+    }
+
+    pub fn cost(&self, node: &N) -> Option<&C> {
+        self.costs.get(node)
+    }
+
+    /// The cheapest path from a source to node, including both ends, or None if node wasn't reached
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.costs.contains_key(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        while let Some(prev) = self.predecessors.get(path.last().unwrap()) {
+            path.push(prev.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// The path to the goal node, if one was reached
+    pub fn goal_path(&self) -> Option<Vec<N>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+
+    /// Record node as settled, returning whether the search should stop because it's a goal
+    fn settle(
+        &mut self,
+        node: N,
+        cost: C,
+        from: Option<N>,
+        goal: Option<&dyn Fn(&N) -> bool>,
+    ) -> bool {
+        if let Some(from) = from {
+            self.predecessors.insert(node.clone(), from);
+        }
+
+        let is_goal = goal.is_some_and(|is_goal| is_goal(&node));
+        if is_goal {
+            self.goal = Some(node.clone());
+        }
+
+        self.costs.insert(node, cost);
+        is_goal
+    }
+}
+
+/// Breadth-first search, where every edge has cost 1.
+///
+/// `neighbors` returns the nodes reachable in one step from a node.  If `goal` is given, the search stops at the
+/// first node it matches.
+pub fn bfs<N, S, I, F>(
+    sources: S,
+    mut neighbors: F,
+    goal: Option<&dyn Fn(&N) -> bool>,
+) -> SearchResult<N, usize>
+where
+    N: Hash + Eq + Clone,
+    S: IntoIterator<Item = N>,
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+{
+    let mut result = SearchResult::new();
+
+    // (path_length, node, predecessor) triples
+    let mut to_visit: VecDeque<(usize, N, Option<N>)> =
+        sources.into_iter().map(|node| (0, node, None)).collect();
+
+    while let Some((len, node, from)) = to_visit.pop_front() {
+        // avoid loops
+        if result.costs.contains_key(&node) {
+            continue;
+        }
+
+        if result.settle(node.clone(), len, from, goal) {
+            break;
+        }
+
+        for neighbor in neighbors(&node) {
+            if !result.costs.contains_key(&neighbor) {
+                to_visit.push_back((len + 1, neighbor, Some(node.clone())));
+            }
+        }
+    }
+
+    result
+}
+
+/// Dijkstra's algorithm for graphs with non-negative edge costs.
+///
+/// `neighbors` returns (node, edge cost) pairs for the edges leaving a node.  If `goal` is given, the search stops
+/// at the first node it matches, which is then the cheapest matching node to reach.
+pub fn dijkstra<N, C, S, I, F>(
+    sources: S,
+    neighbors: F,
+    goal: Option<&dyn Fn(&N) -> bool>,
+) -> SearchResult<N, C>
+where
+    N: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    S: IntoIterator<Item = N>,
+    I: IntoIterator<Item = (N, C)>,
+    F: FnMut(&N) -> I,
+{
+    astar(sources, neighbors, |_| C::default(), goal)
+}
+
+/// A* search for graphs with non-negative edge costs.
+///
+/// Like `dijkstra`, but nodes are explored in order of cost so far plus `heuristic`, an estimate of the remaining
+/// cost to a goal.  Settled nodes are never revisited, so for the costs to be exact the heuristic must be
+/// consistent: `heuristic(a) <= cost(a, b) + heuristic(b)` for every edge, and 0 at goals.  (Merely never
+/// overestimating isn't enough, since a node could then be settled before its cheapest path is found.)  Nodes
+/// that look like a detour are never settled, so only the goal's cost and path are meaningful when a goal is
+/// given.
+pub fn astar<N, C, S, I, F, H>(
+    sources: S,
+    mut neighbors: F,
+    mut heuristic: H,
+    goal: Option<&dyn Fn(&N) -> bool>,
+) -> SearchResult<N, C>
+where
+    N: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    S: IntoIterator<Item = N>,
+    I: IntoIterator<Item = (N, C)>,
+    F: FnMut(&N) -> I,
+    H: FnMut(&N) -> C,
+{
+    let mut result = SearchResult::new();
+
+    // cheapest cost found so far for nodes that have been queued, to avoid queueing worse paths
+    let mut best: HashMap<N, C> = HashMap::new();
+    let mut to_visit = BinaryHeap::new();
+
+    for node in sources {
+        best.insert(node.clone(), C::default());
+        to_visit.push(Queued {
+            priority: heuristic(&node),
+            cost: C::default(),
+            node,
+            from: None,
+        });
+    }
+
+    while let Some(Queued {
+        cost, node, from, ..
+    }) = to_visit.pop()
+    {
+        if result.costs.contains_key(&node) {
+            continue;
+        }
+
+        if result.settle(node.clone(), cost, from, goal) {
+            break;
+        }
+
+        for (neighbor, edge_cost) in neighbors(&node) {
+            let new_cost = cost + edge_cost;
+            if best.get(&neighbor).is_none_or(|&c| new_cost < c) {
+                best.insert(neighbor.clone(), new_cost);
+                to_visit.push(Queued {
+                    priority: new_cost + heuristic(&neighbor),
+                    cost: new_cost,
+                    node: neighbor,
+                    from: Some(node.clone()),
+                });
+            }
+        }
+    }
+
+    result
+}
+
+/// Priority queue entry, ordered so that BinaryHeap pops the lowest priority first
+struct Queued<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+    from: Option<N>,
+}
+
+impl<N, C: Ord> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for Queued<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coord, Grid};
+
+    fn maze() -> Grid<char> {
+        Grid::new(
+            ["S..#....", ".#.#.##.", ".#...#..", ".####.#.", "......#E"]
+                .iter()
+                .map(|row| row.chars().collect())
+                .collect(),
+        )
+    }
+
+    fn open_neighbors(grid: &Grid<char>, coord: Coord) -> Vec<Coord> {
+        grid.neighbor_coords(coord)
+            .filter(|&nc| grid[nc] != '#')
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs_path() {
+        let grid = maze();
+        let end = (4, 7);
+
+        let result = bfs(
+            [(0, 0)],
+            |&coord| open_neighbors(&grid, coord),
+            Some(&|&coord| coord == end),
+        );
+
+        assert_eq!(result.goal, Some(end));
+        assert_eq!(result.cost(&end), Some(&15));
+
+        let path = result.goal_path().unwrap();
+        assert_eq!(path.len(), 16);
+        assert_eq!(path[0], (0, 0));
+        assert!(path
+            .iter()
+            .zip(path.iter().skip(1))
+            .all(|(&a, &b)| grid.neighbor_coords(a).any(|n| n == b)));
+    }
+
+    #[test]
+    fn test_multiple_sources() {
+        // distance along a line from the nearest of two sources
+        let result = bfs(
+            [0, 10],
+            |&n: &i32| [n - 1, n + 1].into_iter().filter(|n| (0..=10).contains(n)),
+            None,
+        );
+
+        assert_eq!(result.cost(&3), Some(&3));
+        assert_eq!(result.cost(&8), Some(&2));
+        assert_eq!(result.path_to(&8), Some(vec![10, 9, 8]));
+        assert_eq!(result.goal, None);
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar_agree() {
+        // moving onto a '.' costs 1, onto a digit costs that digit
+        let grid = Grid::new(
+            [".9...", ".9.1.", "...1.", "1991."]
+                .iter()
+                .map(|row| row.chars().collect())
+                .collect(),
+        );
+        let end: Coord = (3, 4);
+        let weighted_neighbors = |&coord: &Coord| {
+            grid.neighbor_coords(coord)
+                .map(|nc| (nc, grid[nc].to_digit(10).unwrap_or(1)))
+                .collect::<Vec<_>>()
+        };
+
+        let by_dijkstra = dijkstra([(0, 0)], weighted_neighbors, Some(&|&c| c == end));
+        let by_astar = astar(
+            [(0, 0)],
+            weighted_neighbors,
+            |&(row, col)| (row.abs_diff(end.0) + col.abs_diff(end.1)) as u32,
+            Some(&|&c| c == end),
+        );
+
+        assert_eq!(by_dijkstra.cost(&end), Some(&7));
+        assert_eq!(by_astar.cost(&end), Some(&7));
+        assert_eq!(
+            by_dijkstra.goal_path().unwrap().len(),
+            by_astar.goal_path().unwrap().len()
+        );
+
+        // without a goal, every reachable node is settled
+        let all = dijkstra([(0, 0)], weighted_neighbors, None);
+        assert_eq!(all.costs.len(), grid.num_elems());
+    }
+}
@@ -0,0 +1,228 @@
+//! An unbounded grid that only stores the cells that have been set.
+
+use std::{
+    collections::{hash_map, HashMap},
+    fmt::{self, Display},
+};
+
+use crate::{Grid, Neighborhood, SignedCoord};
+
+/// A sparse 2D grid keyed by signed (row, col) coordinates.  Keeps track of the bounding box of the cells it
+/// contains, so it can be converted to a dense `Grid` or rendered.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<SignedCoord, T>,
+    // inclusive (min, max) corners of the occupied cells, or None if empty
+    bounds: Option<(SignedCoord, SignedCoord)>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, coord: SignedCoord) -> Option<&T> {
+        self.cells.get(&coord)
+    }
+
+    pub fn get_mut(&mut self, coord: SignedCoord) -> Option<&mut T> {
+        self.cells.get_mut(&coord)
+    }
+
+    pub fn contains(&self, coord: SignedCoord) -> bool {
+        self.cells.contains_key(&coord)
+    }
+
+    /// Sets a cell, returning its previous value if it was already set
+    pub fn insert(&mut self, coord: SignedCoord, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            None => (coord, coord),
+            Some((min, max)) => (
+                (min.0.min(coord.0), min.1.min(coord.1)),
+                (max.0.max(coord.0), max.1.max(coord.1)),
+            ),
+        });
+
+        self.cells.insert(coord, value)
+    }
+
+    /// Clears a cell, returning its value if it was set
+    pub fn remove(&mut self, coord: SignedCoord) -> Option<T> {
+        let value = self.cells.remove(&coord)?;
+
+        // the bounds can only shrink if the removed cell was on their edge
+        if let Some((min, max)) = self.bounds {
+            if coord.0 == min.0 || coord.0 == max.0 || coord.1 == min.1 || coord.1 == max.1 {
+                self.bounds = Self::compute_bounds(self.cells.keys());
+            }
+        }
+
+        Some(value)
+    }
+
+    fn compute_bounds<'a, I>(coords: I) -> Option<(SignedCoord, SignedCoord)>
+    where
+        I: Iterator<Item = &'a SignedCoord>,
+    {
+        coords.fold(None, |bounds, &(row, col)| match bounds {
+            None => Some(((row, col), (row, col))),
+            Some((min, max)) => Some((
+                (min.0.min(row), min.1.min(col)),
+                (max.0.max(row), max.1.max(col)),
+            )),
+        })
+    }
+
+    /// Inclusive (min, max) corners of the smallest rectangle containing every set cell, or None if empty
+    pub fn bounds(&self) -> Option<(SignedCoord, SignedCoord)> {
+        self.bounds
+    }
+
+    /// Set cells and their values, in no particular order
+    pub fn iter(&self) -> hash_map::Iter<'_, SignedCoord, T> {
+        self.cells.iter()
+    }
+
+    /// Coordinates of all neighbors of coord, whether set or not.  The grid is unbounded, so there are always
+    /// as many as the neighborhood has offsets.
+    pub fn neighbor_coords(
+        coord: SignedCoord,
+        neighborhood: Neighborhood,
+    ) -> impl Iterator<Item = SignedCoord> {
+        neighborhood
+            .offsets()
+            .iter()
+            .map(move |&(dr, dc)| (coord.0 + dr, coord.1 + dc))
+    }
+
+    /// The set neighbors of coord, with their values
+    pub fn neighbors(
+        &self,
+        coord: SignedCoord,
+        neighborhood: Neighborhood,
+    ) -> impl Iterator<Item = (SignedCoord, &T)> {
+        Self::neighbor_coords(coord, neighborhood)
+            .filter_map(move |nc| self.cells.get(&nc).map(|value| (nc, value)))
+    }
+
+    /// Convert to a dense grid covering the bounding box, with `f` producing the value for every cell, set or
+    /// not.  Also returns the coordinate of the dense grid's (0, 0) cell, to translate coordinates between the
+    /// two.  Returns None if the grid is empty.
+    pub fn map_to_grid<U, F>(&self, mut f: F) -> Option<(Grid<U>, SignedCoord)>
+    where
+        F: FnMut(Option<&T>) -> U,
+    {
+        let (min, max) = self.bounds?;
+
+        let rows = (min.0..=max.0)
+            .map(|row| {
+                (min.1..=max.1)
+                    .map(|col| f(self.cells.get(&(row, col))))
+                    .collect()
+            })
+            .collect();
+
+        Some((Grid::new(rows), min))
+    }
+
+    /// Convert to a dense grid covering the bounding box, filling unset cells with `fill`.  See `map_to_grid`.
+    pub fn to_grid(&self, fill: T) -> Option<(Grid<T>, SignedCoord)>
+    where
+        T: Clone,
+    {
+        self.map_to_grid(|value| value.unwrap_or(&fill).clone())
+    }
+}
+
+impl<T> FromIterator<(SignedCoord, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (SignedCoord, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        for (coord, value) in iter {
+            grid.insert(coord, value);
+        }
+        grid
+    }
+}
+
+/// Renders the bounding box, drawing unset cells as '.'
+impl<T: Display> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.map_to_grid(|value| value.map_or(".".to_string(), |v| v.to_string())) {
+            Some((grid, _)) => grid.fmt(f),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds_tracking() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        grid.insert((0, 0), 'a');
+        grid.insert((-2, 3), 'b');
+        grid.insert((1, -1), 'c');
+        assert_eq!(grid.bounds(), Some(((-2, -1), (1, 3))));
+
+        grid.remove((-2, 3));
+        assert_eq!(grid.bounds(), Some(((0, -1), (1, 0))));
+
+        grid.remove((0, 0));
+        grid.remove((1, -1));
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid: SparseGrid<char> = [((0, 0), 'a'), ((0, 1), 'b'), ((1, 1), 'c')]
+            .into_iter()
+            .collect();
+
+        let mut orthogonal = grid
+            .neighbors((0, 0), Neighborhood::Orthogonal)
+            .collect::<Vec<_>>();
+        orthogonal.sort();
+        assert_eq!(orthogonal, vec![((0, 1), &'b')]);
+
+        let mut all = grid
+            .neighbors((0, 0), Neighborhood::All)
+            .collect::<Vec<_>>();
+        all.sort();
+        assert_eq!(all, vec![((0, 1), &'b'), ((1, 1), &'c')]);
+    }
+
+    #[test]
+    fn test_to_grid_and_display() {
+        let grid: SparseGrid<char> = [((-1, 5), '#'), ((0, 7), 'o')].into_iter().collect();
+
+        let (dense, origin) = grid.to_grid('.').unwrap();
+        assert_eq!(origin, (-1, 5));
+        assert_eq!(
+            dense,
+            Grid::new(vec![vec!['#', '.', '.'], vec!['.', '.', 'o']])
+        );
+
+        assert_eq!(grid.to_string(), "#..\n..o");
+    }
+}
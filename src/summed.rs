@@ -0,0 +1,235 @@
+//! 2D prefix sums for O(1) rectangle sums, and sliding-window minimum and maximum over grids.
+
+use std::{
+    collections::VecDeque,
+    ops::{Add, Sub},
+};
+
+use crate::{Coord, Grid};
+
+/// Summed-area table of a grid: entry (r, c) is the sum of every cell above and to the left of (r, c),
+/// exclusive.  Any rectangle's sum is then a combination of its four corner entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummedAreaTable<T> {
+    // (num_rows + 1) x (num_cols + 1), with a zero first row and column
+    sums: Vec<Vec<T>>,
+}
+
+impl<T> SummedAreaTable<T>
+where
+    T: Add<Output = T> + Sub<Output = T> + Copy + Default,
+{
+    pub fn new(grid: &Grid<T>) -> SummedAreaTable<T> {
+        let (num_rows, num_cols) = grid.shape();
+        let mut sums = vec![vec![T::default(); num_cols + 1]; num_rows + 1];
+
+        for r in 0..num_rows {
+            for c in 0..num_cols {
+                sums[r + 1][c + 1] = grid[(r, c)] + sums[r][c + 1] + sums[r + 1][c] - sums[r][c];
+            }
+        }
+
+        SummedAreaTable { sums }
+    }
+
+    /// (rows, cols) of the grid the table was built from
+    pub fn shape(&self) -> (usize, usize) {
+        (self.sums.len() - 1, self.sums[0].len() - 1)
+    }
+
+    /// The sum of the whole grid
+    pub fn total(&self) -> T {
+        let (num_rows, num_cols) = self.shape();
+        self.sums[num_rows][num_cols]
+    }
+
+    /// The sum of the rectangle with the given top left corner and (rows, cols) shape, in O(1).  Panics if
+    /// the rectangle doesn't fit in the grid.
+    pub fn rect_sum(&self, top_left: Coord, shape: (usize, usize)) -> T {
+        let (top, left) = top_left;
+        let (bottom, right) = (top + shape.0, left + shape.1);
+        let (num_rows, num_cols) = self.shape();
+        assert!(
+            bottom <= num_rows && right <= num_cols,
+            "Rectangle at {top_left:?} of shape {shape:?} doesn't fit in a {num_rows}x{num_cols} grid"
+        );
+
+        self.sums[bottom][right] + self.sums[top][left]
+            - self.sums[top][right]
+            - self.sums[bottom][left]
+    }
+
+    /// The sum of every window of the given (rows, cols) shape, indexed by the window's top left corner.
+    /// Panics if the window is empty or larger than the grid.
+    pub fn window_sums(&self, window: (usize, usize)) -> Grid<T> {
+        let (num_rows, num_cols) = window_positions(self.shape(), window);
+        Grid::new(
+            (0..num_rows)
+                .map(|r| {
+                    (0..num_cols)
+                        .map(|c| self.rect_sum((r, c), window))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+}
+
+/// The (rows, cols) of possible top left corners of a window sliding over a grid of the given shape
+fn window_positions(shape: (usize, usize), window: (usize, usize)) -> (usize, usize) {
+    assert!(
+        window.0 > 0 && window.1 > 0 && window.0 <= shape.0 && window.1 <= shape.1,
+        "Invalid window {window:?} for a grid of shape {shape:?}"
+    );
+    (shape.0 - window.0 + 1, shape.1 - window.1 + 1)
+}
+
+/// The best value in each window of width values, where keep(a, b) says a is at least as good as b.
+/// Maintains a deque of indices whose values are strictly worsening, so the front is always the window's best
+/// value and each index is pushed and popped at most once.
+fn sliding_best<T: Copy>(values: &[T], width: usize, keep: impl Fn(&T, &T) -> bool) -> Vec<T> {
+    let mut candidates: VecDeque<usize> = VecDeque::new();
+    let mut best = Vec::with_capacity(values.len() + 1 - width);
+
+    for (i, value) in values.iter().enumerate() {
+        while candidates.back().is_some_and(|&j| keep(value, &values[j])) {
+            candidates.pop_back();
+        }
+        candidates.push_back(i);
+
+        if candidates[0] + width <= i {
+            candidates.pop_front();
+        }
+        if i + 1 >= width {
+            best.push(values[candidates[0]]);
+        }
+    }
+
+    best
+}
+
+impl<T: Copy> Grid<T> {
+    /// O(1) rectangle sums over this grid
+    pub fn summed_area_table(&self) -> SummedAreaTable<T>
+    where
+        T: Add<Output = T> + Sub<Output = T> + Default,
+    {
+        SummedAreaTable::new(self)
+    }
+
+    /// Apply a 1D sliding window to every row, then to every column of the result
+    fn sliding_window(&self, window: (usize, usize), keep: impl Fn(&T, &T) -> bool) -> Grid<T> {
+        let (num_rows, num_cols) = window_positions(self.shape(), window);
+
+        let row_best = self
+            .0
+            .iter()
+            .map(|row| sliding_best(row, window.1, &keep))
+            .collect::<Vec<_>>();
+
+        let mut result = vec![Vec::with_capacity(num_cols); num_rows];
+        for c in 0..num_cols {
+            let column = row_best.iter().map(|row| row[c]).collect::<Vec<_>>();
+            for (r, value) in sliding_best(&column, window.0, &keep)
+                .into_iter()
+                .enumerate()
+            {
+                result[r].push(value);
+            }
+        }
+
+        Grid::new(result)
+    }
+
+    /// The minimum of every window, laid out and checked like `window_sums`.  Each row and column is swept with
+    /// a monotonic queue, so this takes time proportional to the size of the grid, however large the window.
+    pub fn window_min(&self, window: (usize, usize)) -> Grid<T>
+    where
+        T: Ord,
+    {
+        self.sliding_window(window, |a, b| a <= b)
+    }
+
+    /// Like `window_min`, but the maximum of every window
+    pub fn window_max(&self, window: (usize, usize)) -> Grid<T>
+    where
+        T: Ord,
+    {
+        self.sliding_window(window, |a, b| a >= b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forest() -> Grid<i32> {
+        // the day8 example
+        Grid::new(
+            ["30373", "25512", "65332", "33549", "35390"]
+                .iter()
+                .map(|row| {
+                    row.chars()
+                        .map(|ch| ch.to_digit(10).unwrap() as i32)
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    fn brute_force(grid: &Grid<i32>, window: (usize, usize), f: fn(&[i32]) -> i32) -> Grid<i32> {
+        let (num_rows, num_cols) = window_positions(grid.shape(), window);
+        Grid::new(
+            (0..num_rows)
+                .map(|r| {
+                    (0..num_cols)
+                        .map(|c| {
+                            let cells = (r..r + window.0)
+                                .flat_map(|rr| (c..c + window.1).map(move |cc| (rr, cc)))
+                                .map(|coord| grid[coord])
+                                .collect::<Vec<_>>();
+                            f(&cells)
+                        })
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_rect_sums() {
+        let grid = forest();
+        let table = grid.summed_area_table();
+
+        assert_eq!(table.total(), 94);
+        assert_eq!(table.rect_sum((0, 0), (1, 1)), 3);
+        assert_eq!(
+            table.rect_sum((1, 1), (3, 3)),
+            5 + 5 + 1 + 5 + 3 + 3 + 3 + 5 + 4
+        );
+        assert_eq!(table.rect_sum((4, 4), (1, 1)), 0);
+        assert_eq!(table.rect_sum((2, 2), (0, 3)), 0);
+
+        for window in [(1, 1), (2, 3), (5, 5), (5, 1)] {
+            assert_eq!(
+                table.window_sums(window),
+                brute_force(&grid, window, |cells| cells.iter().sum())
+            );
+        }
+    }
+
+    #[test]
+    fn test_window_min_max() {
+        let grid = forest();
+        for window in [(1, 1), (2, 2), (3, 2), (1, 5), (5, 5)] {
+            assert_eq!(
+                grid.window_min(window),
+                brute_force(&grid, window, |cells| *cells.iter().min().unwrap())
+            );
+            assert_eq!(
+                grid.window_max(window),
+                brute_force(&grid, window, |cells| *cells.iter().max().unwrap())
+            );
+        }
+    }
+}
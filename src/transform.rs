@@ -0,0 +1,346 @@
+//! Rotations, flips, crops and padding for `Grid`.  Every transformation except padding is also available as a
+//! zero-copy `GridView`, which just remaps coordinates onto the underlying grid.
+
+use std::ops::Index;
+
+use itertools::Itertools;
+
+use crate::{Coord, Grid};
+
+/// A read-only, transformed window onto a `Grid`.
+///
+/// A view covers a rectangle of the underlying grid, optionally transposed and then flipped along either axis.
+/// Those three flags are enough to express all rotations and reflections, and transformations of a view compose
+/// by adjusting them rather than copying anything.
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    // top-left corner and shape of the covered rectangle, in grid coordinates
+    origin: Coord,
+    extent: (usize, usize),
+    transposed: bool,
+    flip_rows: bool,
+    flip_cols: bool,
+}
+
+impl<'a, T> Clone for GridView<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for GridView<'a, T> {}
+
+impl<'a, T> GridView<'a, T> {
+    pub fn shape(&self) -> (usize, usize) {
+        if self.transposed {
+            (self.extent.1, self.extent.0)
+        } else {
+            self.extent
+        }
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.shape().0
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.shape().1
+    }
+
+    /// Maps a coordinate in this view to the corresponding coordinate in the underlying grid
+    pub fn grid_coord(&self, coord: Coord) -> Coord {
+        let (rows, cols) = self.shape();
+        assert!(
+            coord.0 < rows && coord.1 < cols,
+            "{coord:?} out of bounds for view of shape {:?}",
+            (rows, cols)
+        );
+
+        let (mut row, mut col) = if self.transposed {
+            (coord.1, coord.0)
+        } else {
+            coord
+        };
+        if self.flip_rows {
+            row = self.extent.0 - 1 - row;
+        }
+        if self.flip_cols {
+            col = self.extent.1 - 1 - col;
+        }
+
+        (self.origin.0 + row, self.origin.1 + col)
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<&'a T> {
+        let (rows, cols) = self.shape();
+        if coord.0 < rows && coord.1 < cols {
+            Some(&self.grid[self.grid_coord(coord)])
+        } else {
+            None
+        }
+    }
+
+    /// All coordinates in the view, in row-major order
+    pub fn all_coords(&self) -> impl Iterator<Item = Coord> {
+        (0..self.num_rows()).cartesian_product(0..self.num_cols())
+    }
+
+    pub fn transpose(self) -> Self {
+        GridView {
+            transposed: !self.transposed,
+            ..self
+        }
+    }
+
+    /// Mirror left-to-right
+    pub fn flip_horizontal(self) -> Self {
+        if self.transposed {
+            GridView {
+                flip_rows: !self.flip_rows,
+                ..self
+            }
+        } else {
+            GridView {
+                flip_cols: !self.flip_cols,
+                ..self
+            }
+        }
+    }
+
+    /// Mirror top-to-bottom
+    pub fn flip_vertical(self) -> Self {
+        if self.transposed {
+            GridView {
+                flip_cols: !self.flip_cols,
+                ..self
+            }
+        } else {
+            GridView {
+                flip_rows: !self.flip_rows,
+                ..self
+            }
+        }
+    }
+
+    /// Rotate 90 degrees clockwise
+    pub fn rotate_90(self) -> Self {
+        self.transpose().flip_horizontal()
+    }
+
+    pub fn rotate_180(self) -> Self {
+        self.flip_horizontal().flip_vertical()
+    }
+
+    /// Rotate 270 degrees clockwise, i.e. 90 degrees counter-clockwise
+    pub fn rotate_270(self) -> Self {
+        self.transpose().flip_vertical()
+    }
+
+    /// The `shape` sized rectangle of this view whose top-left corner is at `top_left`
+    pub fn sub_grid(self, top_left: Coord, shape: (usize, usize)) -> Self {
+        assert!(shape.0 > 0 && shape.1 > 0, "Sub-grid must not be empty");
+        let bottom_right = (top_left.0 + shape.0 - 1, top_left.1 + shape.1 - 1);
+
+        // the corners land somewhere in the underlying grid; the covered rectangle is spanned by them whatever
+        // the orientation is
+        let (r0, c0) = self.grid_coord(top_left);
+        let (r1, c1) = self.grid_coord(bottom_right);
+
+        GridView {
+            origin: (r0.min(r1), c0.min(c1)),
+            extent: (r0.abs_diff(r1) + 1, c0.abs_diff(c1) + 1),
+            ..self
+        }
+    }
+
+    /// Crop to the bounding box of the cells matching `pred`, or None if no cells match
+    pub fn crop<F>(self, pred: F) -> Option<Self>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let (min, max) = bounding_box(self.all_coords(), |c| &self[c], pred)?;
+        Some(self.sub_grid(min, (max.0 - min.0 + 1, max.1 - min.1 + 1)))
+    }
+
+    /// Copy the contents of the view into a new grid
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::new(
+            (0..self.num_rows())
+                .map(|row| {
+                    (0..self.num_cols())
+                        .map(|col| self[(row, col)].clone())
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<'a, T> Index<Coord> for GridView<'a, T> {
+    type Output = T;
+
+    fn index(&self, index: Coord) -> &Self::Output {
+        &self.grid[self.grid_coord(index)]
+    }
+}
+
+/// Inclusive (min, max) corners of the coordinates whose values match pred
+fn bounding_box<'a, T: 'a, I, G, F>(coords: I, get: G, mut pred: F) -> Option<(Coord, Coord)>
+where
+    I: Iterator<Item = Coord>,
+    G: Fn(Coord) -> &'a T,
+    F: FnMut(&T) -> bool,
+{
+    coords
+        .filter(|&c| pred(get(c)))
+        .fold(None, |bbox, (row, col)| match bbox {
+            None => Some(((row, col), (row, col))),
+            Some(((min_r, min_c), (max_r, max_c))) => Some((
+                (min_r.min(row), min_c.min(col)),
+                (max_r.max(row), max_c.max(col)),
+            )),
+        })
+}
+
+impl<T> Grid<T> {
+    /// A zero-copy view of the whole grid, which can then be transformed
+    pub fn view(&self) -> GridView<'_, T> {
+        GridView {
+            grid: self,
+            origin: (0, 0),
+            extent: self.shape(),
+            transposed: false,
+            flip_rows: false,
+            flip_cols: false,
+        }
+    }
+
+    /// Inclusive (min, max) corners of the smallest rectangle containing every cell matching `pred`, or None if
+    /// no cells match
+    pub fn bounding_box<F>(&self, pred: F) -> Option<(Coord, Coord)>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        bounding_box(self.all_coords(), |c| &self[c], pred)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn transpose(&self) -> Grid<T> {
+        self.view().transpose().to_grid()
+    }
+
+    /// Mirror left-to-right
+    pub fn flip_horizontal(&self) -> Grid<T> {
+        self.view().flip_horizontal().to_grid()
+    }
+
+    /// Mirror top-to-bottom
+    pub fn flip_vertical(&self) -> Grid<T> {
+        self.view().flip_vertical().to_grid()
+    }
+
+    /// Rotate 90 degrees clockwise
+    pub fn rotate_90(&self) -> Grid<T> {
+        self.view().rotate_90().to_grid()
+    }
+
+    pub fn rotate_180(&self) -> Grid<T> {
+        self.view().rotate_180().to_grid()
+    }
+
+    /// Rotate 270 degrees clockwise, i.e. 90 degrees counter-clockwise
+    pub fn rotate_270(&self) -> Grid<T> {
+        self.view().rotate_270().to_grid()
+    }
+
+    /// Copy of the `shape` sized rectangle whose top-left corner is at `top_left`
+    pub fn sub_grid(&self, top_left: Coord, shape: (usize, usize)) -> Grid<T> {
+        self.view().sub_grid(top_left, shape).to_grid()
+    }
+
+    /// Copy of the bounding box of the cells matching `pred`, or None if no cells match
+    pub fn crop<F>(&self, pred: F) -> Option<Grid<T>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        Some(self.view().crop(pred)?.to_grid())
+    }
+
+    /// Surround the grid with `amount` cells of `fill` on every side
+    pub fn pad(&self, amount: usize, fill: T) -> Grid<T> {
+        let num_cols = self.num_cols() + 2 * amount;
+        let blank_row = vec![fill.clone(); num_cols];
+
+        let mut rows = vec![blank_row.clone(); amount];
+        for row in self.0.iter() {
+            let mut new_row = Vec::with_capacity(num_cols);
+            new_row.extend(std::iter::repeat_n(fill.clone(), amount));
+            new_row.extend_from_slice(row);
+            new_row.extend(std::iter::repeat_n(fill.clone(), amount));
+            rows.push(new_row);
+        }
+        rows.extend(std::iter::repeat_n(blank_row, amount));
+
+        Grid::new(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(rows: &[&str]) -> Grid<char> {
+        Grid::new(rows.iter().map(|r| r.chars().collect()).collect())
+    }
+
+    #[test]
+    fn test_rotations_and_flips() {
+        let g = grid(&["abc", "def"]);
+
+        assert!(g.transpose() == grid(&["ad", "be", "cf"]));
+        assert!(g.rotate_90() == grid(&["da", "eb", "fc"]));
+        assert!(g.rotate_180() == grid(&["fed", "cba"]));
+        assert!(g.rotate_270() == grid(&["cf", "be", "ad"]));
+        assert!(g.flip_horizontal() == grid(&["cba", "fed"]));
+        assert!(g.flip_vertical() == grid(&["def", "abc"]));
+
+        assert!(
+            g.view()
+                .rotate_90()
+                .rotate_90()
+                .rotate_90()
+                .rotate_90()
+                .to_grid()
+                == g
+        );
+        assert!(g.view().rotate_90().flip_horizontal().to_grid() == g.transpose());
+    }
+
+    #[test]
+    fn test_sub_grid_of_transformed_view() {
+        let g = grid(&["abcd", "efgh", "ijkl"]);
+
+        assert!(g.sub_grid((1, 1), (2, 2)) == grid(&["fg", "jk"]));
+
+        // rotated is ["iea", "jfb", "kgc", "lhd"]
+        let rotated = g.view().rotate_90();
+        assert!(rotated.sub_grid((1, 0), (2, 2)).to_grid() == grid(&["jf", "kg"]));
+        assert_eq!(rotated.sub_grid((1, 0), (2, 2))[(1, 1)], 'g');
+    }
+
+    #[test]
+    fn test_crop_and_pad() {
+        let g = grid(&["....", ".#..", "..#.", "...."]);
+
+        assert_eq!(g.bounding_box(|&c| c == '#'), Some(((1, 1), (2, 2))));
+        assert!(g.crop(|&c| c == '#').unwrap() == grid(&["#.", ".#"]));
+        assert!(g.crop(|&c| c == 'x').is_none());
+
+        let cropped = g.crop(|&c| c == '#').unwrap();
+        assert!(cropped.pad(1, '.') == g);
+    }
+}
@@ -0,0 +1,377 @@
+//! 3D grids: a dense, fixed-size `Grid3` and an unbounded, sparse `VoxelSet`.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    ops::{Index, IndexMut},
+};
+
+use crate::Point3;
+
+/// Which surrounding voxels count as neighbors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood3 {
+    /// the 6 voxels sharing a face
+    Faces,
+    /// the 26 voxels sharing a face, edge or corner
+    All,
+}
+
+impl Neighborhood3 {
+    /// Offsets from a voxel to each of its neighbors
+    pub fn offsets(&self) -> &'static [Point3<isize>] {
+        // face offsets first, so they're a prefix of the full list
+        const OFFSETS: [Point3<isize>; 26] = {
+            let mut offsets = [Point3 { x: 0, y: 0, z: 0 }; 26];
+            let (mut faces, mut others) = (0, 6);
+            let mut i: isize = 0;
+            while i < 27 {
+                let (x, y, z) = (i / 9 - 1, i / 3 % 3 - 1, i % 3 - 1);
+                match x.abs() + y.abs() + z.abs() {
+                    0 => {}
+                    1 => {
+                        offsets[faces] = Point3 { x, y, z };
+                        faces += 1;
+                    }
+                    _ => {
+                        offsets[others] = Point3 { x, y, z };
+                        others += 1;
+                    }
+                }
+                i += 1;
+            }
+            offsets
+        };
+
+        match self {
+            Neighborhood3::Faces => &OFFSETS[..6],
+            Neighborhood3::All => &OFFSETS,
+        }
+    }
+}
+
+/// A dense 3D grid indexed by `Point3<usize>`, stored with x varying fastest
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid3<T> {
+    dims: Point3<usize>,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid3<T> {
+    pub fn new(dims: Point3<usize>, fill: T) -> Grid3<T> {
+        Grid3 {
+            dims,
+            cells: vec![fill; dims.x * dims.y * dims.z],
+        }
+    }
+}
+
+impl<T> Grid3<T> {
+    pub fn from_fn(dims: Point3<usize>, mut f: impl FnMut(Point3<usize>) -> T) -> Grid3<T> {
+        let cells = all_coords(dims).map(&mut f).collect();
+        Grid3 { dims, cells }
+    }
+
+    /// The size of the grid along each axis
+    pub fn dims(&self) -> Point3<usize> {
+        self.dims
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, coord: Point3<usize>) -> bool {
+        coord.x < self.dims.x && coord.y < self.dims.y && coord.z < self.dims.z
+    }
+
+    fn index_of(&self, coord: Point3<usize>) -> Option<usize> {
+        self.contains(coord)
+            .then(|| (coord.z * self.dims.y + coord.y) * self.dims.x + coord.x)
+    }
+
+    pub fn get(&self, coord: Point3<usize>) -> Option<&T> {
+        self.index_of(coord).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, coord: Point3<usize>) -> Option<&mut T> {
+        self.index_of(coord).map(|i| &mut self.cells[i])
+    }
+
+    /// All coordinates in the grid, in storage order
+    pub fn all_coords(&self) -> impl Iterator<Item = Point3<usize>> {
+        all_coords(self.dims)
+    }
+
+    /// Coordinates of the neighbors of coord that are inside the grid
+    pub fn neighbor_coords(
+        &self,
+        coord: Point3<usize>,
+        neighborhood: Neighborhood3,
+    ) -> impl Iterator<Item = Point3<usize>> {
+        let dims = self.dims;
+        VoxelSet::neighbor_coords(to_signed(coord), neighborhood).filter_map(move |n| {
+            let n = Point3::new(
+                usize::try_from(n.x).ok()?,
+                usize::try_from(n.y).ok()?,
+                usize::try_from(n.z).ok()?,
+            );
+            (n.x < dims.x && n.y < dims.y && n.z < dims.z).then_some(n)
+        })
+    }
+
+    /// The inclusive (min, max) corners of the smallest box containing every cell matching pred, or None if
+    /// no cell does
+    pub fn bounding_box(
+        &self,
+        pred: impl Fn(&T) -> bool,
+    ) -> Option<(Point3<usize>, Point3<usize>)> {
+        self.all_coords()
+            .zip(&self.cells)
+            .filter(|(_, cell)| pred(cell))
+            .map(|(coord, _)| (coord, coord))
+            .reduce(|(min, max), (coord, _)| (min.min(coord), max.max(coord)))
+    }
+}
+
+impl<T> Index<Point3<usize>> for Grid3<T> {
+    type Output = T;
+
+    fn index(&self, coord: Point3<usize>) -> &Self::Output {
+        let i = self
+            .index_of(coord)
+            .unwrap_or_else(|| panic!("{coord} is outside a grid of size {}", self.dims));
+        &self.cells[i]
+    }
+}
+
+impl<T> IndexMut<Point3<usize>> for Grid3<T> {
+    fn index_mut(&mut self, coord: Point3<usize>) -> &mut Self::Output {
+        let i = self
+            .index_of(coord)
+            .unwrap_or_else(|| panic!("{coord} is outside a grid of size {}", self.dims));
+        &mut self.cells[i]
+    }
+}
+
+fn all_coords(dims: Point3<usize>) -> impl Iterator<Item = Point3<usize>> {
+    (0..dims.z).flat_map(move |z| {
+        (0..dims.y).flat_map(move |y| (0..dims.x).map(move |x| Point3::new(x, y, z)))
+    })
+}
+
+fn to_signed(coord: Point3<usize>) -> Point3<isize> {
+    Point3::new(coord.x as isize, coord.y as isize, coord.z as isize)
+}
+
+/// An unbounded set of voxels with signed coordinates
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VoxelSet {
+    voxels: HashSet<Point3<isize>>,
+}
+
+impl VoxelSet {
+    pub fn new() -> VoxelSet {
+        VoxelSet::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.voxels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.voxels.is_empty()
+    }
+
+    pub fn contains(&self, voxel: Point3<isize>) -> bool {
+        self.voxels.contains(&voxel)
+    }
+
+    /// Returns true if the voxel wasn't already set
+    pub fn insert(&mut self, voxel: Point3<isize>) -> bool {
+        self.voxels.insert(voxel)
+    }
+
+    /// Returns true if the voxel was set
+    pub fn remove(&mut self, voxel: Point3<isize>) -> bool {
+        self.voxels.remove(&voxel)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Point3<isize>> + '_ {
+        self.voxels.iter().copied()
+    }
+
+    /// Coordinates of all the neighbors of voxel, whether or not they're set
+    pub fn neighbor_coords(
+        voxel: Point3<isize>,
+        neighborhood: Neighborhood3,
+    ) -> impl Iterator<Item = Point3<isize>> {
+        neighborhood
+            .offsets()
+            .iter()
+            .map(move |&offset| voxel + offset)
+    }
+
+    /// The neighbors of voxel that are set
+    pub fn neighbors(
+        &self,
+        voxel: Point3<isize>,
+        neighborhood: Neighborhood3,
+    ) -> impl Iterator<Item = Point3<isize>> + '_ {
+        VoxelSet::neighbor_coords(voxel, neighborhood).filter(|n| self.contains(*n))
+    }
+
+    /// The inclusive (min, max) corners of the smallest box containing every voxel, or None if empty
+    pub fn bounding_box(&self) -> Option<(Point3<isize>, Point3<isize>)> {
+        self.iter()
+            .map(|voxel| (voxel, voxel))
+            .reduce(|(min, max), (voxel, _)| (min.min(voxel), max.max(voxel)))
+    }
+
+    /// The number of voxel faces that aren't shared with another voxel, including faces of internal cavities
+    pub fn surface_area(&self) -> usize {
+        self.iter()
+            .flat_map(|voxel| VoxelSet::neighbor_coords(voxel, Neighborhood3::Faces))
+            .filter(|n| !self.contains(*n))
+            .count()
+    }
+
+    /// The empty voxels reachable from outside the bounding box through face-adjacent empty voxels, within a
+    /// one-voxel margin around the bounding box
+    pub fn exterior(&self) -> VoxelSet {
+        let Some((min, max)) = self.bounding_box() else {
+            return VoxelSet::new();
+        };
+        let one = Point3::new(1, 1, 1);
+        let (min, max) = (min - one, max + one);
+        let in_bounds = |v: Point3<isize>| {
+            (min.x..=max.x).contains(&v.x)
+                && (min.y..=max.y).contains(&v.y)
+                && (min.z..=max.z).contains(&v.z)
+        };
+
+        // the margin guarantees min is empty and that the empty space around the shape is connected
+        let mut exterior = VoxelSet::new();
+        let mut queue = VecDeque::from([min]);
+        exterior.insert(min);
+
+        while let Some(voxel) = queue.pop_front() {
+            for n in VoxelSet::neighbor_coords(voxel, Neighborhood3::Faces) {
+                if in_bounds(n) && !self.contains(n) && exterior.insert(n) {
+                    queue.push_back(n);
+                }
+            }
+        }
+
+        exterior
+    }
+
+    /// The number of voxel faces exposed to the outside, ignoring internal cavities
+    pub fn exterior_surface_area(&self) -> usize {
+        let exterior = self.exterior();
+        self.iter()
+            .flat_map(|voxel| VoxelSet::neighbor_coords(voxel, Neighborhood3::Faces))
+            .filter(|n| exterior.contains(*n))
+            .count()
+    }
+
+    /// Convert to a dense grid of booleans covering the bounding box, along with the coordinates of the
+    /// grid's origin.  Returns None if the set is empty.
+    pub fn to_grid(&self) -> Option<(Grid3<bool>, Point3<isize>)> {
+        let (min, max) = self.bounding_box()?;
+        let extent = max - min;
+        let dims = Point3::new(
+            extent.x as usize + 1,
+            extent.y as usize + 1,
+            extent.z as usize + 1,
+        );
+        let grid = Grid3::from_fn(dims, |coord| self.contains(to_signed(coord) + min));
+        Some((grid, min))
+    }
+}
+
+impl FromIterator<Point3<isize>> for VoxelSet {
+    fn from_iter<I: IntoIterator<Item = Point3<isize>>>(iter: I) -> Self {
+        VoxelSet {
+            voxels: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<Point3<isize>> for VoxelSet {
+    fn extend<I: IntoIterator<Item = Point3<isize>>>(&mut self, iter: I) {
+        self.voxels.extend(iter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid3() {
+        let grid = Grid3::from_fn(Point3::new(4, 3, 2), |p| p.x + 10 * p.y + 100 * p.z);
+        assert_eq!(grid.len(), 24);
+        assert_eq!(grid[Point3::new(3, 2, 1)], 123);
+        assert_eq!(grid.get(Point3::new(4, 0, 0)), None);
+
+        let corner = Point3::new(0, 0, 0);
+        assert_eq!(
+            grid.neighbor_coords(corner, Neighborhood3::Faces).count(),
+            3
+        );
+        assert_eq!(grid.neighbor_coords(corner, Neighborhood3::All).count(), 7);
+        assert_eq!(
+            grid.neighbor_coords(Point3::new(1, 1, 0), Neighborhood3::All)
+                .count(),
+            17
+        );
+
+        assert_eq!(
+            grid.bounding_box(|&v| v % 10 == 1 && v > 10),
+            Some((Point3::new(1, 0, 0), Point3::new(1, 2, 1)))
+        );
+        assert_eq!(grid.bounding_box(|&v| v > 1000), None);
+    }
+
+    #[test]
+    fn test_surface_area() {
+        let voxels: VoxelSet = [
+            (2, 2, 2),
+            (1, 2, 2),
+            (3, 2, 2),
+            (2, 1, 2),
+            (2, 3, 2),
+            (2, 2, 1),
+            (2, 2, 3),
+            (2, 2, 4),
+            (2, 2, 6),
+            (1, 2, 5),
+            (3, 2, 5),
+            (2, 1, 5),
+            (2, 3, 5),
+        ]
+        .into_iter()
+        .map(|(x, y, z)| Point3::new(x, y, z))
+        .collect();
+
+        assert_eq!(voxels.surface_area(), 64);
+        // the air pocket at (2, 2, 5) is excluded
+        assert_eq!(voxels.exterior_surface_area(), 58);
+        assert!(!voxels.exterior().contains(Point3::new(2, 2, 5)));
+
+        let (grid, origin) = voxels.to_grid().unwrap();
+        assert_eq!(origin, Point3::new(1, 1, 1));
+        assert_eq!(grid.dims(), Point3::new(3, 3, 6));
+        assert_eq!(grid.cells.iter().filter(|&&set| set).count(), 13);
+        assert_eq!(
+            voxels
+                .neighbors(Point3::new(2, 2, 2), Neighborhood3::All)
+                .count(),
+            6
+        );
+    }
+}